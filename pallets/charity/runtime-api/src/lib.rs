@@ -0,0 +1,48 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+#![allow(clippy::too_many_arguments)]
+#![allow(clippy::unnecessary_mut_passed)]
+
+use parity_scale_codec::{Codec, Decode, Encode};
+use sp_runtime::{DispatchError, Perbill, RuntimeDebug};
+use sp_std::vec::Vec;
+
+/// A snapshot of the charity's headline figures, so a dashboard can fetch them in one call
+#[derive(Encode, Decode, Clone, PartialEq, Eq, Default, RuntimeDebug)]
+pub struct CharityStats<Balance> {
+	/// The balance currently held in the pot
+	pub pot: Balance,
+	/// Everything ever donated
+	pub total_donated: Balance,
+	/// Everything ever paid out of the pot to recipients
+	pub total_allocated: Balance,
+	/// How many accounts currently hold a donation record
+	pub distinct_donors: u32,
+	/// How many donations have been made
+	pub donation_count: u32,
+}
+
+// Here we declare the runtime API. It is implemented in the `impl_runtime_apis!` block of any
+// runtime that includes the charity pallet (see `runtimes/super-runtime/src/lib.rs`)
+sp_api::decl_runtime_apis! {
+	pub trait CharityApi<AccountId: Codec, Balance: Codec> {
+		/// The balance currently held in the charity's pot
+		fn pot_balance() -> Balance;
+		/// The account that holds the pot, so clients need not derive it from the `ModuleId`
+		fn pot_account() -> AccountId;
+		/// Whether `allocate` would pay out `amount` right now without the pot being reaped
+		fn can_allocate(amount: Balance) -> bool;
+		/// The 1-based place of `who` on the top donors leaderboard, if they are on it
+		fn donation_rank(who: AccountId) -> Option<u32>;
+		/// The pot balance left after allocating `amount`, or why the allocation would fail
+		fn simulate_allocation(amount: Balance) -> Result<Balance, DispatchError>;
+		/// Every registered cause, with the funds currently set aside for it
+		fn causes() -> Vec<(Vec<u8>, Balance)>;
+		/// `who`'s recorded contribution as a share of all donors' recorded contributions, which is
+		/// the share of the spendable pot `refund_all` would return to them, before rounding
+		fn pot_share_of(who: AccountId) -> Perbill;
+		/// What is still to be released to `dest` under vesting grants
+		fn pending_vesting(dest: AccountId) -> Balance;
+		/// The pot's headline figures, gathered in one call
+		fn stats() -> CharityStats<Balance>;
+	}
+}
@@ -0,0 +1,509 @@
+//! Benchmarks for the charity pallet
+
+#![cfg(feature = "runtime-benchmarks")]
+
+use super::*;
+
+use frame_benchmarking::{
+	account, benchmarks_instance, impl_benchmark_test_suite, whitelisted_caller,
+};
+use frame_system::RawOrigin;
+
+const SEED: u32 = 0;
+
+/// The smallest amount that can be both donated and moved between accounts
+fn unit<T: Config<I>, I: Instance>() -> BalanceOf<T, I> {
+	T::MinimumDonation::get().max(T::Currency::minimum_balance())
+}
+
+benchmarks_instance! {
+	donate {
+		// The donor's free balance, in multiples of the smallest donation
+		let b in 2 .. 1_000;
+		// The length of the memo
+		let m in 0 .. T::MaxMemoLength::get();
+
+		let donor: T::AccountId = whitelisted_caller();
+		let amount = unit::<T, I>();
+		T::Currency::make_free_balance_be(&donor, amount * b.into());
+
+		// Stock the matching pool so the donation is matched, the most expensive path
+		T::Currency::make_free_balance_be(
+			&Module::<T, I>::matching_account_id(),
+			T::Currency::minimum_balance() + amount,
+		);
+		<MatchingPool<T, I>>::put(amount);
+	}: _(RawOrigin::Signed(donor.clone()), amount, vec![b'm'; m as usize], Some(0))
+	verify {
+		assert_eq!(Module::<T, I>::donation_of(&donor), amount);
+		assert!(Module::<T, I>::matching_pool().is_zero());
+		assert!(Module::<T, I>::nonce_seen(&donor, 0));
+	}
+
+	donate_all {
+		let donor: T::AccountId = whitelisted_caller();
+		let amount = unit::<T, I>();
+		T::Currency::make_free_balance_be(&donor, amount * 2u32.into());
+	}: _(RawOrigin::Signed(donor.clone()))
+	verify {
+		assert_eq!(T::Currency::free_balance(&donor), T::Currency::minimum_balance());
+	}
+
+	donate_on_behalf {
+		let payer: T::AccountId = whitelisted_caller();
+		let beneficiary: T::AccountId = account("beneficiary", 0, SEED);
+		let amount = unit::<T, I>();
+		T::Currency::make_free_balance_be(&payer, amount * 2u32.into());
+	}: _(RawOrigin::Signed(payer), beneficiary.clone(), amount)
+	verify {
+		assert_eq!(Module::<T, I>::donation_of(&beneficiary), amount);
+	}
+
+	donate_and_allocate {
+		let donor: T::AccountId = whitelisted_caller();
+		let dest: T::AccountId = account("recipient", 0, SEED);
+		let amount = unit::<T, I>();
+		T::Currency::make_free_balance_be(&donor, amount * 2u32.into());
+	}: _(RawOrigin::Signed(donor.clone()), dest.clone(), amount)
+	verify {
+		assert_eq!(Module::<T, I>::donation_of(&donor), amount);
+		assert_eq!(T::Currency::free_balance(&dest), amount);
+	}
+
+	allocate {
+		// The size of the pot, in multiples of the smallest donation
+		let p in 2 .. 1_000;
+		// Whether the recipient's account already exists (1) or has to be created (0)
+		let e in 0 .. 1;
+
+		let amount = unit::<T, I>();
+		let funder: T::AccountId = account("funder", 0, SEED);
+		T::Currency::make_free_balance_be(&funder, amount * (p + 1).into());
+		Module::<T, I>::donate(RawOrigin::Signed(funder).into(), amount * p.into(), Vec::new(), None)?;
+		let dest: T::AccountId = account("recipient", 0, SEED);
+		if e == 1 {
+			T::Currency::make_free_balance_be(&dest, amount);
+		}
+		let dest_before = T::Currency::free_balance(&dest);
+		let origin = T::AllocationOrigin::successful_origin();
+	}: {
+		Module::<T, I>::allocate(origin, dest.clone(), amount)?;
+	}
+	verify {
+		assert_eq!(T::Currency::free_balance(&dest), dest_before + amount);
+	}
+
+	allocate_all {
+		let amount = unit::<T, I>();
+		let funder: T::AccountId = account("funder", 0, SEED);
+		T::Currency::make_free_balance_be(&funder, amount * 3u32.into());
+		Module::<T, I>::donate(RawOrigin::Signed(funder).into(), amount * 2u32.into(), Vec::new(), None)?;
+		let dest: T::AccountId = account("recipient", 0, SEED);
+	}: _(RawOrigin::Root, dest.clone(), false)
+	verify {
+		assert!(T::Currency::free_balance(&dest) >= amount * 2u32.into());
+	}
+
+	allocate_percentage {
+		let amount = unit::<T, I>();
+		let funder: T::AccountId = account("funder", 0, SEED);
+		T::Currency::make_free_balance_be(&funder, amount * 3u32.into());
+		Module::<T, I>::donate(RawOrigin::Signed(funder).into(), amount * 2u32.into(), Vec::new(), None)?;
+		let dest: T::AccountId = account("recipient", 0, SEED);
+	}: _(RawOrigin::Root, dest.clone(), Perbill::from_percent(50))
+	verify {
+		assert!(!T::Currency::free_balance(&dest).is_zero());
+	}
+
+	allocate_noop {
+		let dest: T::AccountId = account("recipient", 0, SEED);
+		let origin = T::AllocationOrigin::successful_origin();
+	}: {
+		Module::<T, I>::allocate(origin, dest.clone(), Zero::zero())?;
+	}
+	verify {
+		assert!(T::Currency::free_balance(&dest).is_zero());
+	}
+
+	donate_to_cause {
+		// The length of the cause's name
+		let n in 0 .. T::MaxCauseNameLength::get();
+
+		let donor: T::AccountId = whitelisted_caller();
+		let amount = unit::<T, I>();
+		T::Currency::make_free_balance_be(&donor, amount * 2u32.into());
+		let cause = vec![b'c'; n as usize];
+	}: _(RawOrigin::Signed(donor), cause.clone(), amount)
+	verify {
+		assert_eq!(Module::<T, I>::cause_balance(&cause), amount);
+	}
+
+	donate_many {
+		// The number of causes given to, each of them new
+		let n in 1 .. T::MaxBatchSize::get().min(T::MaxCauses::get());
+
+		let donor: T::AccountId = whitelisted_caller();
+		let amount = unit::<T, I>();
+		T::Currency::make_free_balance_be(&donor, amount * (n + 1).into());
+		let donations = (0..n)
+			.map(|i| {
+				let mut cause = vec![b'c'; T::MaxCauseNameLength::get() as usize];
+				cause[..4].copy_from_slice(&i.to_le_bytes());
+				(cause, amount)
+			})
+			.collect::<Vec<_>>();
+		let first = donations[0].0.clone();
+	}: _(RawOrigin::Signed(donor), donations)
+	verify {
+		assert_eq!(Module::<T, I>::cause_balance(&first), amount);
+	}
+
+	allocate_from_cause {
+		let amount = unit::<T, I>();
+		let donor: T::AccountId = account("donor", 0, SEED);
+		T::Currency::make_free_balance_be(&donor, amount * 3u32.into());
+		let cause = vec![b'c'; T::MaxCauseNameLength::get() as usize];
+		Module::<T, I>::donate_to_cause(
+			RawOrigin::Signed(donor).into(),
+			cause.clone(),
+			amount * 2u32.into(),
+		)?;
+		let dest: T::AccountId = account("recipient", 0, SEED);
+	}: _(RawOrigin::Root, cause.clone(), dest.clone(), amount)
+	verify {
+		assert_eq!(Module::<T, I>::cause_balance(&cause), amount);
+		assert_eq!(T::Currency::free_balance(&dest), amount);
+	}
+
+	donate_asset {
+		let donor: T::AccountId = whitelisted_caller();
+		let amount: AssetBalanceOf<T, I> = 10u32.into();
+		let asset = T::Assets::fund_for_benchmark(&donor, amount);
+	}: _(RawOrigin::Signed(donor), asset, amount)
+	verify {
+		assert_eq!(Module::<T, I>::asset_pot(asset), amount);
+	}
+
+	allocate_asset {
+		let funder: T::AccountId = account("funder", 0, SEED);
+		let amount: AssetBalanceOf<T, I> = 10u32.into();
+		let asset = T::Assets::fund_for_benchmark(&funder, amount);
+		Module::<T, I>::donate_asset(RawOrigin::Signed(funder).into(), asset, amount)?;
+		let dest: T::AccountId = account("recipient", 0, SEED);
+	}: _(RawOrigin::Root, asset, dest, amount)
+	verify {
+		assert!(Module::<T, I>::asset_pot(asset).is_zero());
+	}
+
+	allocate_batch {
+		// The number of recipients paid
+		let n in 1 .. T::MaxBatchSize::get();
+
+		let amount = unit::<T, I>();
+		let funder: T::AccountId = account("funder", 0, SEED);
+		T::Currency::make_free_balance_be(&funder, amount * (n + 1).into());
+		Module::<T, I>::donate(RawOrigin::Signed(funder).into(), amount * n.into(), Vec::new(), None)?;
+		let payments = (0..n)
+			.map(|i| (account("recipient", i, SEED), amount))
+			.collect::<Vec<(T::AccountId, _)>>();
+		let first = payments[0].0.clone();
+	}: _(RawOrigin::Root, payments)
+	verify {
+		assert_eq!(T::Currency::free_balance(&first), amount);
+	}
+
+	allocate_proportional {
+		// The number of recipients paid
+		let n in 1 .. T::MaxBatchSize::get();
+
+		let amount = unit::<T, I>();
+		let funder: T::AccountId = account("funder", 0, SEED);
+		T::Currency::make_free_balance_be(&funder, amount * (n + 1).into());
+		Module::<T, I>::donate(RawOrigin::Signed(funder).into(), amount * n.into(), Vec::new(), None)?;
+		let recipients = (0..n)
+			.map(|i| (account("recipient", i, SEED), Perbill::from_rational_approximation(1, n)))
+			.collect::<Vec<(T::AccountId, _)>>();
+		let first = recipients[0].0.clone();
+	}: _(RawOrigin::Root, recipients, RemainderPolicy::GiveToFirst)
+	verify {
+		assert!(!T::Currency::free_balance(&first).is_zero());
+	}
+
+	withdraw_donation {
+		let donor: T::AccountId = whitelisted_caller();
+		let amount = unit::<T, I>();
+		T::Currency::make_free_balance_be(&donor, amount * 2u32.into());
+		Module::<T, I>::donate(RawOrigin::Signed(donor.clone()).into(), amount, Vec::new(), None)?;
+	}: _(RawOrigin::Signed(donor.clone()), amount)
+	verify {
+		assert_eq!(Module::<T, I>::donation_of(&donor), Zero::zero());
+	}
+
+	schedule_donation {
+		let donor: T::AccountId = whitelisted_caller();
+		let amount = unit::<T, I>();
+		let deposit = T::RecurringDonationDeposit::get();
+		T::Currency::make_free_balance_be(&donor, deposit + T::Currency::minimum_balance());
+	}: _(RawOrigin::Signed(donor.clone()), amount, 10u32.into())
+	verify {
+		assert_eq!(Module::<T, I>::recurring_donation_of(&donor), Some((amount, 10u32.into())));
+		assert_eq!(Module::<T, I>::recurring_donation_deposit_of(&donor), deposit);
+	}
+
+	fund_pot_from_account {
+		let source: T::AccountId = account("treasury", 0, SEED);
+		let amount = unit::<T, I>();
+		T::Currency::make_free_balance_be(&source, amount * 2u32.into());
+		let pot_before = Module::<T, I>::pot();
+	}: _(RawOrigin::Root, source, amount)
+	verify {
+		assert_eq!(Module::<T, I>::pot(), pot_before + amount);
+	}
+
+	force_donate {
+		let from: T::AccountId = account("donor", 0, SEED);
+		let amount = unit::<T, I>();
+		T::Currency::make_free_balance_be(&from, amount * 2u32.into());
+	}: _(RawOrigin::Root, from.clone(), amount)
+	verify {
+		assert_eq!(Module::<T, I>::donation_of(&from), amount);
+	}
+
+	merge_donor_records {
+		let from: T::AccountId = account("donor", 0, SEED);
+		let into: T::AccountId = account("donor", 1, SEED);
+		let amount = unit::<T, I>();
+		for donor in [&from, &into].iter() {
+			T::Currency::make_free_balance_be(donor, amount * 2u32.into());
+			Module::<T, I>::donate(RawOrigin::Signed((*donor).clone()).into(), amount, Vec::new(), None)?;
+		}
+	}: _(RawOrigin::Root, from.clone(), into.clone())
+	verify {
+		assert!(Module::<T, I>::donation_of(&from).is_zero());
+		assert_eq!(Module::<T, I>::donation_of(&into), amount * 2u32.into());
+	}
+
+	cancel_recurring_donation {
+		let donor: T::AccountId = whitelisted_caller();
+		let amount = unit::<T, I>();
+		let deposit = T::RecurringDonationDeposit::get();
+		T::Currency::make_free_balance_be(&donor, deposit + T::Currency::minimum_balance());
+		Module::<T, I>::schedule_donation(
+			RawOrigin::Signed(donor.clone()).into(),
+			amount,
+			10u32.into(),
+		)?;
+	}: _(RawOrigin::Signed(donor.clone()))
+	verify {
+		assert_eq!(Module::<T, I>::recurring_donation_of(&donor), None);
+		assert_eq!(Module::<T, I>::recurring_donation_count(), 0);
+	}
+
+	fund_matching {
+		let sponsor: T::AccountId = whitelisted_caller();
+		let amount = unit::<T, I>();
+		T::Currency::make_free_balance_be(&sponsor, amount * 2u32.into());
+	}: _(RawOrigin::Signed(sponsor), amount)
+	verify {
+		assert_eq!(Module::<T, I>::matching_pool(), amount);
+	}
+
+	migrate_pot {
+		let amount = unit::<T, I>();
+		let funder: T::AccountId = account("funder", 0, SEED);
+		T::Currency::make_free_balance_be(&funder, amount * 2u32.into());
+		Module::<T, I>::donate(RawOrigin::Signed(funder).into(), amount, Vec::new(), None)?;
+	}: _(RawOrigin::Root, *b"Giving!!")
+	verify {
+		assert_eq!(Module::<T, I>::account_id(), ModuleId(*b"Giving!!").into_account());
+	}
+
+	allocate_with_vesting {
+		let amount = unit::<T, I>();
+		let funder: T::AccountId = account("funder", 0, SEED);
+		T::Currency::make_free_balance_be(&funder, amount * 11u32.into());
+		Module::<T, I>::donate(RawOrigin::Signed(funder).into(), amount * 10u32.into(), Vec::new(), None)?;
+		let dest: T::AccountId = account("recipient", 0, SEED);
+	}: _(RawOrigin::Root, dest.clone(), amount * 10u32.into(), amount, 10u32.into())
+	verify {
+		assert!(Module::<T, I>::vesting_schedule_of(&dest).is_some());
+	}
+
+	cancel_vesting {
+		let amount = unit::<T, I>();
+		let funder: T::AccountId = account("funder", 0, SEED);
+		T::Currency::make_free_balance_be(&funder, amount * 11u32.into());
+		Module::<T, I>::donate(RawOrigin::Signed(funder).into(), amount * 10u32.into(), Vec::new(), None)?;
+		let dest: T::AccountId = account("recipient", 0, SEED);
+		Module::<T, I>::allocate_with_vesting(
+			RawOrigin::Root.into(),
+			dest.clone(),
+			amount * 10u32.into(),
+			amount,
+			10u32.into(),
+		)?;
+	}: _(RawOrigin::Root, dest.clone())
+	verify {
+		assert!(Module::<T, I>::vesting_schedule_of(&dest).is_none());
+	}
+
+	schedule_allocation {
+		let dest: T::AccountId = account("recipient", 0, SEED);
+		let amount = unit::<T, I>();
+		let at = frame_system::Module::<T>::block_number() + 10u32.into();
+	}: _(RawOrigin::Root, dest.clone(), amount, at)
+	verify {
+		let event: <T as Config<I>>::Event = RawEvent::AllocationScheduled(dest, amount, at).into();
+		let event: <T as frame_system::Config>::Event = event.into();
+		assert_eq!(frame_system::Module::<T>::events().last().map(|r| &r.event), Some(&event));
+	}
+
+	refund_all {
+		// The number of donors to refund
+		let d in 1 .. 100;
+
+		let amount = unit::<T, I>();
+		for i in 0 .. d {
+			let donor: T::AccountId = account("donor", i, SEED);
+			T::Currency::make_free_balance_be(&donor, amount * 2u32.into());
+			Module::<T, I>::donate(RawOrigin::Signed(donor).into(), amount, Vec::new(), None)?;
+		}
+	}: _(RawOrigin::Root, d)
+	verify {
+		assert!(Module::<T, I>::total_donated().is_zero());
+	}
+
+	set_allocation_limit {
+		let limit = Some(unit::<T, I>());
+	}: _(RawOrigin::Root, limit)
+	verify {
+		assert_eq!(Module::<T, I>::allocation_limit(), limit);
+	}
+
+	propose_allocation {
+		let amount = unit::<T, I>();
+		let funder: T::AccountId = account("funder", 0, SEED);
+		T::Currency::make_free_balance_be(&funder, amount * 3u32.into());
+		Module::<T, I>::donate(RawOrigin::Signed(funder).into(), amount * 2u32.into(), Vec::new(), None)?;
+		let dest: T::AccountId = account("recipient", 0, SEED);
+	}: _(RawOrigin::Root, dest, amount)
+	verify {
+		assert!(Module::<T, I>::pending_allocation(0).is_some());
+	}
+
+	cancel_allocation {
+		let amount = unit::<T, I>();
+		let funder: T::AccountId = account("funder", 0, SEED);
+		T::Currency::make_free_balance_be(&funder, amount * 3u32.into());
+		Module::<T, I>::donate(RawOrigin::Signed(funder).into(), amount * 2u32.into(), Vec::new(), None)?;
+		let dest: T::AccountId = account("recipient", 0, SEED);
+		Module::<T, I>::propose_allocation(RawOrigin::Root.into(), dest, amount)?;
+	}: _(RawOrigin::Root, 0)
+	verify {
+		assert!(Module::<T, I>::pending_allocation(0).is_none());
+	}
+
+	set_allowlist_enabled {
+	}: _(RawOrigin::Root, true)
+	verify {
+		assert!(Module::<T, I>::allowlist_enabled());
+	}
+
+	add_to_allowlist {
+		let who: T::AccountId = account("donor", 0, SEED);
+	}: _(RawOrigin::Root, who.clone())
+	verify {
+		assert!(Module::<T, I>::is_allowed(&who));
+	}
+
+	remove_from_allowlist {
+		let who: T::AccountId = account("donor", 0, SEED);
+		Allowlist::<T, I>::insert(&who, true);
+	}: _(RawOrigin::Root, who.clone())
+	verify {
+		assert!(!Module::<T, I>::is_allowed(&who));
+	}
+
+	hide_from_leaderboard {
+		let donor: T::AccountId = whitelisted_caller();
+		let amount = unit::<T, I>();
+		T::Currency::make_free_balance_be(&donor, amount * 2u32.into());
+		Module::<T, I>::donate(RawOrigin::Signed(donor.clone()).into(), amount, Vec::new(), None)?;
+	}: _(RawOrigin::Signed(donor.clone()))
+	verify {
+		assert!(Module::<T, I>::is_anonymous(&donor));
+		assert!(Module::<T, I>::top_donors().is_empty());
+	}
+
+	show_on_leaderboard {
+		let donor: T::AccountId = whitelisted_caller();
+		let amount = unit::<T, I>();
+		T::Currency::make_free_balance_be(&donor, amount * 2u32.into());
+		Module::<T, I>::donate(RawOrigin::Signed(donor.clone()).into(), amount, Vec::new(), None)?;
+		Module::<T, I>::hide_from_leaderboard(RawOrigin::Signed(donor.clone()).into())?;
+	}: _(RawOrigin::Signed(donor.clone()))
+	verify {
+		assert_eq!(Module::<T, I>::donation_rank(&donor), Some(1));
+	}
+
+	pause {
+	}: _(RawOrigin::Root)
+	verify {
+		assert!(Module::<T, I>::is_paused());
+	}
+
+	unpause {
+		<Paused<I>>::put(true);
+	}: _(RawOrigin::Root)
+	verify {
+		assert!(!Module::<T, I>::is_paused());
+	}
+
+	sweep_to_account {
+		let amount = unit::<T, I>();
+		let funder: T::AccountId = account("funder", 0, SEED);
+		T::Currency::make_free_balance_be(&funder, amount * 2u32.into());
+		Module::<T, I>::donate(RawOrigin::Signed(funder).into(), amount, Vec::new(), None)?;
+		<Paused<I>>::put(true);
+		let dest: T::AccountId = account("dest", 0, SEED);
+	}: _(RawOrigin::Root, dest.clone())
+	verify {
+		assert!(Module::<T, I>::pot().is_zero());
+		assert!(T::Currency::free_balance(&dest) >= amount);
+	}
+
+	transfer_between_causes {
+		let donor: T::AccountId = whitelisted_caller();
+		let amount = unit::<T, I>();
+		T::Currency::make_free_balance_be(&donor, amount * 3u32.into());
+		let from = vec![b'f'; T::MaxCauseNameLength::get() as usize];
+		let to = vec![b't'; T::MaxCauseNameLength::get() as usize];
+		Module::<T, I>::donate_to_cause(
+			RawOrigin::Signed(donor).into(),
+			from.clone(),
+			amount * 2u32.into(),
+		)?;
+	}: _(RawOrigin::Root, from.clone(), to.clone(), amount)
+	verify {
+		assert_eq!(Module::<T, I>::cause_balance(&from), amount);
+		assert_eq!(Module::<T, I>::cause_balance(&to), amount);
+	}
+
+	close_cause {
+		let donor: T::AccountId = whitelisted_caller();
+		let amount = unit::<T, I>();
+		T::Currency::make_free_balance_be(&donor, amount * 2u32.into());
+		let cause = vec![b'c'; T::MaxCauseNameLength::get() as usize];
+		Module::<T, I>::donate_to_cause(RawOrigin::Signed(donor).into(), cause.clone(), amount)?;
+	}: _(RawOrigin::Root, cause.clone())
+	verify {
+		assert!(Module::<T, I>::cause_balance(&cause).is_zero());
+		assert!(Module::<T, I>::cause_list().is_empty());
+	}
+}
+
+impl_benchmark_test_suite!(
+	Module,
+	crate::mock::new_test_ext(),
+	crate::mock::TestRuntime
+);
@@ -0,0 +1,111 @@
+//! A `Vec` whose length is capped by a runtime constant
+//!
+//! FRAME 3.0 does not ship a bounded vector, so the charity provides its own. It encodes exactly
+//! like the `Vec` it wraps, so values already in storage decode unchanged, but decoding rejects
+//! anything longer than the bound, and the only way to build one is through `TryFrom`.
+
+use parity_scale_codec::{Decode, Encode, EncodeLike, Error, Input};
+use sp_std::{convert::TryFrom, fmt, marker::PhantomData, ops::Deref, prelude::*};
+
+use frame_support::traits::Get;
+
+/// A `Vec<T>` holding at most `S::get()` items
+pub struct BoundedVec<T, S>(Vec<T>, PhantomData<S>);
+
+impl<T, S> BoundedVec<T, S> {
+	/// Give up the bound and take back the inner `Vec`
+	pub fn into_inner(self) -> Vec<T> {
+		self.0
+	}
+}
+
+impl<T, S: Get<u32>> BoundedVec<T, S> {
+	/// The most items this vector may hold
+	pub fn bound() -> usize {
+		S::get() as usize
+	}
+}
+
+impl<T, S: Get<u32>> TryFrom<Vec<T>> for BoundedVec<T, S> {
+	type Error = ();
+
+	fn try_from(items: Vec<T>) -> Result<Self, ()> {
+		if items.len() <= Self::bound() {
+			Ok(BoundedVec(items, PhantomData))
+		} else {
+			Err(())
+		}
+	}
+}
+
+impl<T, S> Default for BoundedVec<T, S> {
+	fn default() -> Self {
+		BoundedVec(Vec::new(), PhantomData)
+	}
+}
+
+impl<T, S> Deref for BoundedVec<T, S> {
+	type Target = Vec<T>;
+
+	fn deref(&self) -> &Vec<T> {
+		&self.0
+	}
+}
+
+impl<T, S> From<BoundedVec<T, S>> for Vec<T> {
+	fn from(bounded: BoundedVec<T, S>) -> Vec<T> {
+		bounded.0
+	}
+}
+
+// The impls below are written by hand rather than derived, so that they don't demand anything of
+// `S`, which is usually a `parameter_types!` type with no other traits.
+
+impl<T: Clone, S> Clone for BoundedVec<T, S> {
+	fn clone(&self) -> Self {
+		BoundedVec(self.0.clone(), PhantomData)
+	}
+}
+
+impl<T: PartialEq, S> PartialEq for BoundedVec<T, S> {
+	fn eq(&self, other: &Self) -> bool {
+		self.0 == other.0
+	}
+}
+
+impl<T: Eq, S> Eq for BoundedVec<T, S> {}
+
+impl<T: PartialEq, S> PartialEq<Vec<T>> for BoundedVec<T, S> {
+	fn eq(&self, other: &Vec<T>) -> bool {
+		&self.0 == other
+	}
+}
+
+impl<T: fmt::Debug, S> fmt::Debug for BoundedVec<T, S> {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		self.0.fmt(f)
+	}
+}
+
+impl<T: Encode, S> Encode for BoundedVec<T, S> {
+	fn size_hint(&self) -> usize {
+		self.0.size_hint()
+	}
+
+	fn using_encoded<R, F: FnOnce(&[u8]) -> R>(&self, f: F) -> R {
+		self.0.using_encoded(f)
+	}
+}
+
+impl<T: Encode, S> EncodeLike for BoundedVec<T, S> {}
+
+// The encodings match, so a plain `Vec` can be used to look up a bounded storage key
+impl<T: Encode, S> EncodeLike<BoundedVec<T, S>> for Vec<T> {}
+impl<T: Encode, S> EncodeLike<BoundedVec<T, S>> for &Vec<T> {}
+
+impl<T: Decode, S: Get<u32>> Decode for BoundedVec<T, S> {
+	fn decode<I: Input>(input: &mut I) -> Result<Self, Error> {
+		let items = Vec::<T>::decode(input)?;
+		Self::try_from(items).map_err(|_| "BoundedVec exceeds its bound".into())
+	}
+}
@@ -7,136 +7,2559 @@
 //! * An imablance can be absorbed from somewhere else in the runtime.
 //! Funds can only be allocated by a root call to the `allocate` extrinsic/
 #![cfg_attr(not(feature = "std"), no_std)]
+#![recursion_limit = "256"]
 
-use sp_runtime::{traits::AccountIdConversion, ModuleId};
-use sp_std::prelude::*;
+use parity_scale_codec::{Decode, Encode};
+use sp_runtime::{
+	helpers_128bit::multiply_by_rational,
+	traits::{
+		AccountIdConversion, AtLeast32BitUnsigned, CheckedAdd, Dispatchable, SaturatedConversion,
+		Saturating, Zero,
+	},
+	DispatchError, ModuleId, Perbill, RuntimeDebug,
+};
+use sp_std::{convert::TryInto, prelude::*};
 
 use frame_support::{
-	decl_event, decl_module, decl_storage,
-	dispatch::{DispatchError, DispatchResult},
-	traits::{Currency, ExistenceRequirement::AllowDeath, Imbalance, OnUnbalanced},
+	decl_error, decl_event, decl_module, decl_storage,
+	dispatch::{DispatchResult, DispatchResultWithPostInfo},
+	ensure,
+	traits::{
+		schedule::{Anon as ScheduleAnon, DispatchTime, HARD_DEADLINE},
+		Currency, EnsureOrigin, ExistenceRequirement,
+		ExistenceRequirement::{AllowDeath, KeepAlive},
+		Get, Imbalance, OnUnbalanced, ReservableCurrency, WithdrawReasons,
+	},
+	transactional,
+	weights::{Weight, WithPostDispatchInfo},
+	IterableStorageMap, Parameter,
 };
 use frame_system::{ensure_root, ensure_signed};
 
+#[cfg(feature = "runtime-benchmarks")]
+mod benchmarking;
+pub mod bounded;
+#[cfg(test)]
+mod mock;
 #[cfg(test)]
 mod tests;
+pub mod weights;
 
-type BalanceOf<T> =
-	<<T as Config>::Currency as Currency<<T as frame_system::Config>::AccountId>>::Balance;
-type NegativeImbalanceOf<T> = <<T as Config>::Currency as Currency<
+pub use bounded::BoundedVec;
+pub use charity_runtime_api::CharityStats;
+pub use weights::WeightInfo;
+
+type BalanceOf<T, I = DefaultInstance> =
+	<<T as Config<I>>::Currency as Currency<<T as frame_system::Config>::AccountId>>::Balance;
+type NegativeImbalanceOf<T, I = DefaultInstance> = <<T as Config<I>>::Currency as Currency<
 	<T as frame_system::Config>::AccountId,
 >>::NegativeImbalance;
+type PositiveImbalanceOf<T, I = DefaultInstance> = <<T as Config<I>>::Currency as Currency<
+	<T as frame_system::Config>::AccountId,
+>>::PositiveImbalance;
+type AssetIdOf<T, I = DefaultInstance> =
+	<<T as Config<I>>::Assets as AssetTransfer<<T as frame_system::Config>::AccountId>>::AssetId;
+type AssetBalanceOf<T, I = DefaultInstance> =
+	<<T as Config<I>>::Assets as AssetTransfer<<T as frame_system::Config>::AccountId>>::Balance;
+type MemoOf<T, I = DefaultInstance> = BoundedVec<u8, <T as Config<I>>::MaxMemoLength>;
+type CauseNameOf<T, I = DefaultInstance> = BoundedVec<u8, <T as Config<I>>::MaxCauseNameLength>;
+type PendingAllocationOf<T, I = DefaultInstance> = PendingAllocation<
+	<T as frame_system::Config>::AccountId,
+	BalanceOf<T, I>,
+	<T as frame_system::Config>::BlockNumber,
+>;
+
+/// The layout of the pallet's storage, bumped whenever a migration is needed
+///
+/// This FRAME version has no `StorageVersion` of its own, so the pallet keeps track itself.
+#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, Default, RuntimeDebug)]
+pub enum StorageVersion {
+	/// Storage from before `TotalDonated` was tracked
+	#[default]
+	V1,
+	/// `TotalDonated` holds the sum of all donor credits
+	V2,
+}
+
+/// Where an absorbed imbalance came from, so that analytics can tell the sources apart
+#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, RuntimeDebug)]
+pub enum ImbalanceKind {
+	/// Transaction fees
+	Fees,
+	/// Slashing, for example of a misbehaving validator
+	Slash,
+	/// Funds another pallet burned
+	Burn,
+	/// Absorbed through plain `OnUnbalanced`, which carries no source
+	Unknown,
+}
+
+/// Where `allocate_proportional` sends what rounding each share down leaves over
+#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, RuntimeDebug)]
+pub enum RemainderPolicy {
+	/// Leave the dust in the pot
+	KeepInPot,
+	/// Add the dust to the first recipient's payment
+	GiveToFirst,
+	/// Add the dust to the last recipient's payment
+	GiveToLast,
+}
+
+/// A durable record of a single donation, kept for accounting
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug)]
+pub struct DonationReceipt<AccountId, Balance, BlockNumber> {
+	/// Who the donation is credited to
+	pub donor: AccountId,
+	/// How much reached the pot
+	pub amount: Balance,
+	/// The block in which the donation was made
+	pub block: BlockNumber,
+}
+
+type DonationReceiptOf<T, I = DefaultInstance> = DonationReceipt<
+	<T as frame_system::Config>::AccountId,
+	BalanceOf<T, I>,
+	<T as frame_system::Config>::BlockNumber,
+>;
+
+/// An allocation that has been proposed but not yet paid
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug)]
+pub struct PendingAllocation<AccountId, Balance, BlockNumber> {
+	/// Who will be paid
+	pub dest: AccountId,
+	/// How much they will be paid
+	pub amount: Balance,
+	/// The block in which the payment is made, unless cancelled first
+	pub execute_at: BlockNumber,
+}
+
+/// A grant that is paid out of the pot a little at a time
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug)]
+pub struct VestingSchedule<Balance, BlockNumber> {
+	/// What is still to be paid
+	pub remaining: Balance,
+	/// How much is paid each block
+	pub per_block: Balance,
+	/// The first block in which a payment is made
+	pub start: BlockNumber,
+}
+
+/// Something that wants to know when a donation has been made
+pub trait OnDonationHandler<AccountId, Balance> {
+	/// `who` has donated `amount` to the charity
+	fn on_donation(who: &AccountId, amount: Balance);
+}
+
+impl<AccountId, Balance> OnDonationHandler<AccountId, Balance> for () {
+	fn on_donation(_who: &AccountId, _amount: Balance) {}
+}
+
+/// Something that wants to know when the charity has allocated funds
+pub trait OnAllocationHandler<AccountId, Balance> {
+	/// The charity has paid `amount` to `dest`
+	fn on_allocation(dest: &AccountId, amount: Balance);
+}
+
+impl<AccountId, Balance> OnAllocationHandler<AccountId, Balance> for () {
+	fn on_allocation(_dest: &AccountId, _amount: Balance) {}
+}
+
+/// Decides whether a donation may be made, on top of the charity's own checks
+///
+/// Campaigns with rules of their own, such as only accepting round amounts, can enforce them here.
+pub trait ValidateDonation<AccountId, Balance> {
+	/// Fail if `who` may not donate `amount`
+	fn validate(who: &AccountId, amount: Balance) -> DispatchResult;
+}
+
+impl<AccountId, Balance> ValidateDonation<AccountId, Balance> for () {
+	fn validate(_who: &AccountId, _amount: Balance) -> DispatchResult {
+		Ok(())
+	}
+}
 
-/// Hardcoded pallet ID; used to create the special Pot Account
-/// Must be exactly 8 characters long
-const PALLET_ID: ModuleId = ModuleId(*b"Charity!");
+/// Moves assets other than the native currency between accounts
+///
+/// Runtimes with `pallet-assets` can implement this with a thin wrapper around its transfers.
+pub trait AssetTransfer<AccountId> {
+	/// Identifies an asset
+	type AssetId: Parameter + Copy;
+	/// Amounts of an asset
+	type Balance: Parameter + AtLeast32BitUnsigned + Default + Copy;
 
-pub trait Config: frame_system::Config {
+	/// Move `amount` of `asset` from `source` to `dest`
+	fn transfer(
+		asset: Self::AssetId,
+		source: &AccountId,
+		dest: &AccountId,
+		amount: Self::Balance,
+	) -> DispatchResult;
+
+	/// Give `who` `amount` of some asset, returning which, so benchmarks have an asset to move
+	#[cfg(feature = "runtime-benchmarks")]
+	fn fund_for_benchmark(who: &AccountId, amount: Self::Balance) -> Self::AssetId;
+}
+
+/// For runtimes without other assets; every transfer fails
+impl<AccountId> AssetTransfer<AccountId> for () {
+	type AssetId = u32;
+	type Balance = u128;
+
+	fn transfer(_: u32, _: &AccountId, _: &AccountId, _: u128) -> DispatchResult {
+		Err(DispatchError::Other("no assets are available"))
+	}
+
+	#[cfg(feature = "runtime-benchmarks")]
+	fn fund_for_benchmark(_: &AccountId, _: u128) -> u32 {
+		0
+	}
+}
+
+pub trait Config<I: Instance = DefaultInstance>: frame_system::Config {
 	/// The overarching event type.
-	type Event: From<Event<Self>> + Into<<Self as frame_system::Config>::Event>;
+	type Event: From<Event<Self, I>> + Into<<Self as frame_system::Config>::Event>;
 	/// The currency type that the charity deals in
-	type Currency: Currency<Self::AccountId>;
+	type Currency: ReservableCurrency<Self::AccountId>;
+	/// The ID the pot account is derived from, until `migrate_pot` moves it
+	type ModuleId: Get<ModuleId>;
+	/// Whether the pot's funds are kept reserved on the pallet account rather than free. Reserved
+	/// funds cannot be moved by accident; they are only unreserved at the moment they are paid out.
+	type UseReserved: Get<bool>;
+	/// Whether an allocation may take the pot below its existential deposit. `AllowDeath` lets it
+	/// reap the pot account; `KeepAlive` makes such an allocation fail instead. This governs the
+	/// pot as the sender: a recipient is never reaped by being paid.
+	type AllocationExistenceRequirement: Get<ExistenceRequirement>;
+	/// The smallest donation the charity will accept
+	type MinimumDonation: Get<BalanceOf<Self, I>>;
+	/// Donations must be a whole multiple of this amount. A unit of one accepts any amount.
+	type DonationUnit: Get<BalanceOf<Self, I>>;
+	/// The longest memo, in bytes, that may accompany a donation
+	type MaxMemoLength: Get<u32>;
+	/// The most the pot may hold. Donations beyond this are sent on to `OverflowDestination`.
+	type MaxPotBalance: Get<BalanceOf<Self, I>>;
+	/// Where the part of a donation that would take the pot above `MaxPotBalance` is sent
+	type OverflowDestination: Get<Self::AccountId>;
+	/// The longest name, in bytes, that a cause may have
+	type MaxCauseNameLength: Get<u32>;
+	/// How many distinct causes may be registered in `CauseList`
+	type MaxCauses: Get<u32>;
+	/// The most donations that may be bundled into one `donate_many` call
+	type MaxBatchSize: Get<u32>;
+	/// Told about every donation that reaches the pot, so other pallets can react to it
+	type OnDonation: OnDonationHandler<Self::AccountId, BalanceOf<Self, I>>;
+	/// Told about every allocation paid, whatever the route, so other pallets can track
+	/// disbursements
+	type OnAllocation: OnAllocationHandler<Self::AccountId, BalanceOf<Self, I>>;
+	/// Asked about every donation before it is made; a donation it rejects fails with its error
+	type DonationValidator: ValidateDonation<Self::AccountId, BalanceOf<Self, I>>;
+	/// The assets, besides `Currency`, that the charity accepts
+	type Assets: AssetTransfer<Self::AccountId>;
+	/// How many blocks a proposed allocation waits before it is paid
+	type AllocationDelay: Get<Self::BlockNumber>;
+	/// Pot sizes worth celebrating; `MilestoneReached` is emitted the first time each is reached
+	type Milestones: Get<Vec<BalanceOf<Self, I>>>;
+	/// The share of each absorbed imbalance that goes into the pot; the rest is burned
+	type AbsorbFraction: Get<Perbill>;
+	/// How often, in blocks, the pot balance is recorded in `PotHistory`; zero turns this off
+	type SnapshotInterval: Get<Self::BlockNumber>;
+	/// How many snapshots are kept before the oldest is pruned
+	type MaxSnapshots: Get<u32>;
+	/// How many donors the `TopDonors` leaderboard holds
+	type MaxLeaderboard: Get<u32>;
+	/// How many blocks may pass between two donations for the second to extend a donor's streak
+	type StreakWindow: Get<Self::BlockNumber>;
+	/// Streak lengths worth celebrating; `StreakExtended` is emitted whenever a donor reaches one
+	type StreakThresholds: Get<Vec<u32>>;
+	/// The share of each donation skimmed off as a platform fee before it reaches the pot
+	type DonationFee: Get<Perbill>;
+	/// The account that receives the `DonationFee`
+	type FeeCollector: Get<Self::AccountId>;
+	/// The balance allocations must always leave in the pot, on top of the existential deposit
+	/// floor; zero leaves allocations unrestricted
+	type MinPotReserve: Get<BalanceOf<Self, I>>;
+	/// How many blocks a donor must wait after donating before they may donate again, by any
+	/// route; zero turns the cool-down off
+	type DonationCooldown: Get<Self::BlockNumber>;
+	/// The length, in blocks, of the periods `PerDonorPeriodCap` applies to; zero turns the cap
+	/// off
+	type DonationPeriod: Get<Self::BlockNumber>;
+	/// The most any one donor may give within a single `DonationPeriod`
+	type PerDonorPeriodCap: Get<BalanceOf<Self, I>>;
+	/// The pot size below which `PotRunningLow` is emitted; zero turns the warning off
+	type LowPotThreshold: Get<BalanceOf<Self, I>>;
+	/// The most that governance allocations, by whatever route, may pay out of the pot within one
+	/// block; `donate_and_allocate` only passes a donor's own funds through, so it isn't counted
+	type MaxSpendPerBlock: Get<BalanceOf<Self, I>>;
+	/// The share of every allocation paid to `OperationsAccount` to fund the charity's running
+	/// costs; the recipient gets the rest
+	type AllocationFee: Get<Perbill>;
+	/// The account that receives the `AllocationFee`
+	type OperationsAccount: Get<Self::AccountId>;
+	/// The most recurring donations that may be scheduled at once, which bounds the work
+	/// `on_initialize` does in each block
+	type MaxRecurringDonations: Get<u32>;
+	/// The deposit reserved from a donor for as long as their recurring donation is scheduled
+	type RecurringDonationDeposit: Get<BalanceOf<Self, I>>;
+	/// The runtime call that `schedule_allocation` hands to the scheduler
+	type ScheduledCall: Parameter + Dispatchable<Origin = Self::Origin> + From<Call<Self, I>>;
+	/// The origin the scheduler dispatches with, such as the runtime's `OriginCaller`
+	type PalletsOrigin: From<frame_system::RawOrigin<Self::AccountId>>;
+	/// The runtime's scheduler, typically `pallet-scheduler`
+	type Scheduler: ScheduleAnon<Self::BlockNumber, Self::ScheduledCall, Self::PalletsOrigin>;
+	/// The origin that may `allocate` funds from the pot, such as root or a council majority
+	type AllocationOrigin: EnsureOrigin<Self::Origin>;
+	/// Weight information for the extrinsics in this pallet
+	type WeightInfo: WeightInfo;
 }
 
 decl_storage! {
-	trait Store for Module<T: Config> as SimpleTreasury {
-		// No storage items of our own, but we still need decl_storage to initialize the pot
+	trait Store for Module<T: Config<I>, I: Instance = DefaultInstance> as SimpleTreasury {
+		/// The total amount ever donated to the charity. Unlike the pot, this is not reduced by
+		/// allocations; only donors withdrawing their own contributions lower it.
+		TotalDonated get(fn total_donated): BalanceOf<T, I>;
+
+		/// The total amount ever paid out of the pot to recipients.
+		TotalAllocated get(fn total_allocated): BalanceOf<T, I>;
+
+		/// The cumulative amount each donor has contributed.
+		Donations get(fn donation_of):
+			map hasher(blake2_128_concat) T::AccountId => BalanceOf<T, I>;
+
+		/// Whether the charity is paused. While paused, no donations or allocations are accepted.
+		Paused get(fn is_paused): bool;
+
+		/// Donors' standing orders to give `amount` every `interval` blocks.
+		RecurringDonations get(fn recurring_donation_of):
+			map hasher(blake2_128_concat) T::AccountId => Option<(BalanceOf<T, I>, T::BlockNumber)>;
+
+		/// How many entries `RecurringDonations` holds, kept within `MaxRecurringDonations`.
+		RecurringDonationCount get(fn recurring_donation_count): u32;
+
+		/// The deposit each donor has reserved for their recurring donation, returned when they
+		/// cancel it.
+		RecurringDonationDeposits get(fn recurring_donation_deposit_of):
+			map hasher(blake2_128_concat) T::AccountId => BalanceOf<T, I>;
+
+		/// Funds put up by sponsors to match donations 1:1. These are held on the matching
+		/// account, on top of its existential deposit.
+		MatchingPool get(fn matching_pool): BalanceOf<T, I>;
+
+		/// The part of the pot set aside for each named cause.
+		Causes get(fn cause_balance):
+			map hasher(blake2_128_concat) CauseNameOf<T, I> => BalanceOf<T, I>;
+
+		/// The name of every cause ever donated to, in the order they were first given to.
+		CauseList get(fn cause_list): Vec<CauseNameOf<T, I>>;
+
+		/// The number of donations ever made.
+		DonationCount get(fn donation_count): u32;
+
+		/// The number of donors with a contribution on record. A donor who withdraws everything
+		/// stops counting, and counts again if they come back.
+		DistinctDonors get(fn distinct_donors): u32;
+
+		/// The biggest single donation so far, and who made it.
+		LargestDonation get(fn largest_donation): (T::AccountId, BalanceOf<T, I>);
+
+		/// The block of each donor's most recent donation.
+		LastDonationBlock get(fn last_donation_block):
+			map hasher(blake2_128_concat) T::AccountId => T::BlockNumber;
+
+		/// The index of the `DonationPeriod` each donor last gave in, and how much they have given
+		/// in it so far.
+		DonatedInPeriod get(fn donated_in_period):
+			map hasher(blake2_128_concat) T::AccountId => (T::BlockNumber, BalanceOf<T, I>);
+
+		/// How many donations in a row each donor has made, each within `StreakWindow` blocks of
+		/// the one before.
+		DonationStreak get(fn donation_streak):
+			map hasher(blake2_128_concat) T::AccountId => u32;
+
+		/// How much of each non-native asset the pot holds.
+		AssetPot get(fn asset_pot):
+			map hasher(twox_64_concat) AssetIdOf<T, I> => AssetBalanceOf<T, I>;
+
+		/// The most a single allocation may move, if anything.
+		AllocationLimit get(fn allocation_limit): Option<BalanceOf<T, I>>;
+
+		/// Whether donations are restricted to accounts on the `Allowlist`.
+		AllowlistEnabled get(fn allowlist_enabled): bool;
+
+		/// Accounts allowed to donate while `AllowlistEnabled` is set.
+		Allowlist get(fn is_allowed):
+			map hasher(blake2_128_concat) T::AccountId => bool;
+
+		/// The ID the pot account is derived from, once it has been migrated away from `ModuleId`.
+		PotId get(fn pot_id): Option<ModuleId>;
+
+		/// Funds held back from the pot while a governance proposal that would spend them is
+		/// pending. They are reserved on the pot account and do not count towards `pot()`.
+		LockedFunds get(fn locked_funds): BalanceOf<T, I>;
+
+		/// Grants being streamed out of the pot, keyed by recipient. The unpaid part of each grant
+		/// is locked so it can't be allocated elsewhere.
+		VestingSchedules get(fn vesting_schedule_of):
+			map hasher(blake2_128_concat) T::AccountId
+			=> Option<VestingSchedule<BalanceOf<T, I>, T::BlockNumber>>;
+
+		/// Allocations waiting out `AllocationDelay` before they are paid. Their funds are locked
+		/// in the meantime.
+		PendingAllocations get(fn pending_allocation):
+			map hasher(twox_64_concat) u32
+			=> Option<PendingAllocationOf<T, I>>;
+
+		/// The ID the next proposed allocation will be given.
+		NextAllocationId get(fn next_allocation_id): u32;
+
+		/// A receipt for every donation, by receipt ID.
+		Receipts get(fn receipt):
+			map hasher(twox_64_concat) u64 => Option<DonationReceiptOf<T, I>>;
+
+		/// The ID the next donation receipt will be given.
+		NextReceiptId get(fn next_receipt_id): u64;
+
+		/// Whether the pot was below `LowPotThreshold` at the end of the last block.
+		WasLow get(fn was_low): bool;
+
+		/// How much has been allocated so far in this block, counted against `MaxSpendPerBlock`.
+		SpentThisBlock get(fn spent_this_block): BalanceOf<T, I>;
+
+		/// The layout the pallet's storage is in. New chains start at the latest version.
+		CurrentStorageVersion get(fn storage_version)
+			build(|_: &GenesisConfig<T, I>| StorageVersion::V2): StorageVersion;
+
+		/// The milestones the pot has reached so far.
+		CrossedMilestones get(fn milestone_crossed):
+			map hasher(twox_64_concat) BalanceOf<T, I> => bool;
+
+		/// The pot balance at the end of every `SnapshotInterval`th block, for the last
+		/// `MaxSnapshots` snapshots.
+		PotHistory get(fn pot_at):
+			map hasher(twox_64_concat) T::BlockNumber => Option<BalanceOf<T, I>>;
+
+		/// The blocks with an entry in `PotHistory`, oldest first.
+		SnapshotBlocks get(fn snapshot_blocks): Vec<T::BlockNumber>;
+
+		/// The `MaxLeaderboard` largest donors by recorded contribution, largest first.
+		TopDonors get(fn top_donors): Vec<(T::AccountId, BalanceOf<T, I>)>;
+
+		/// Donors who have asked to be kept off `TopDonors`. Their donations are recorded as usual.
+		AnonymousDonors get(fn is_anonymous):
+			map hasher(blake2_128_concat) T::AccountId => bool;
+
+		/// The donation nonces each donor has already used.
+		SeenNonces get(fn nonce_seen):
+			double_map hasher(blake2_128_concat) T::AccountId, hasher(twox_64_concat) u32 => bool;
 	}
 	add_extra_genesis {
-		build(|_config| {
+		/// Donations to record at genesis. Each amount is moved from the donor's account into the
+		/// pot, so donors must be endowed by the balances pallet's genesis.
+		config(initial_donations): Vec<(T::AccountId, BalanceOf<T, I>)>;
+		/// Funds minted straight into the pot at genesis. They count towards `TotalDonated` but
+		/// are not credited to any donor. The pot never starts below the existential deposit.
+		config(initial_endowment): BalanceOf<T, I>;
+		build(|config: &GenesisConfig<T, I>| {
 			// Create the charity's pot of funds, and ensure it has the minimum required deposit
+			let pot = <Module<T, I>>::account_id();
+			let endowment = config.initial_endowment;
+			if T::UseReserved::get() {
+				// Reserved funds sit on top of the deposit, just as donations do
+				let _ = T::Currency::make_free_balance_be(
+					&pot,
+					T::Currency::minimum_balance().saturating_add(endowment),
+				);
+				T::Currency::reserve(&pot, endowment)
+					.expect("the endowment was just minted into the pot");
+			} else {
+				let _ = T::Currency::make_free_balance_be(
+					&pot,
+					T::Currency::minimum_balance().max(endowment),
+				);
+			}
+			<TotalDonated<T, I>>::put(endowment);
+			// Likewise for the account holding the matching pool
 			let _ = T::Currency::make_free_balance_be(
-				&<Module<T>>::account_id(),
+				&<Module<T, I>>::matching_account_id(),
 				T::Currency::minimum_balance(),
 			);
+
+			for (donor, amount) in &config.initial_donations {
+				<Module<T, I>>::deposit_into_pot(donor, *amount)
+					.expect("initial donors must be able to afford their donations");
+				<TotalDonated<T, I>>::mutate(|total| *total = total.saturating_add(*amount));
+				<Module<T, I>>::note_first_donation(donor, *amount);
+				<Donations<T, I>>::mutate(donor, |given| *given = given.saturating_add(*amount));
+				<Module<T, I>>::update_top_donors(donor, <Module<T, I>>::donation_of(donor));
+			}
 		});
 	}
 }
 
 decl_event!(
-	pub enum Event<T>
+	pub enum Event<T, I = DefaultInstance>
 	where
-		Balance = BalanceOf<T>,
+		Balance = BalanceOf<T, I>,
 		<T as frame_system::Config>::AccountId,
+		<T as frame_system::Config>::BlockNumber,
+		AssetId = AssetIdOf<T, I>,
+		AssetBalance = AssetBalanceOf<T, I>,
+		Memo = MemoOf<T, I>,
+		CauseName = CauseNameOf<T, I>,
 	{
-		/// Donor has made a charitable donation to the charity
-		DonationReceived(AccountId, Balance, Balance),
-		/// An imbalance from elsewhere in the runtime has been absorbed by the Charity
-		ImbalanceAbsorbed(Balance, Balance),
+		/// Donor has made a charitable donation to the charity.
+		/// `gross` is what the donor gave and `net` what reached the pot, after any
+		/// `DonationFee` and anything that overflowed `MaxPotBalance`.
+		/// \[donor, gross, net, pot, memo, donation_count, donor_total, receipt_id\]
+		DonationReceived(AccountId, Balance, Balance, Balance, Memo, u32, Balance, u64),
+		/// An imbalance from elsewhere in the runtime has been absorbed by the Charity.
+		/// \[amount, new_pot_total, source\]
+		ImbalanceAbsorbed(Balance, Balance, ImbalanceKind),
+		/// The pot has backed a positive imbalance. \[amount, new_pot_total\]
+		PositiveImbalanceSettled(Balance, Balance),
 		/// Charity has allocated funds to a cause
 		FundsAllocated(AccountId, Balance, Balance),
+		/// An allocation failed because the pot could not cover it.
+		/// \[requested, available, shortfall\]
+		AllocationShortfall(Balance, Balance, Balance),
+		/// An operations fee has been skimmed off an allocation. \[recipient, fee\]
+		AllocationFeeCharged(AccountId, Balance),
+		/// An allocation has been handed to the scheduler. \[dest, amount, at\]
+		AllocationScheduled(AccountId, Balance, BlockNumber),
+		/// One payment in a batch allocation could not be made. \[recipient, amount\]
+		AllocationFailed(AccountId, Balance),
+		/// Donor has withdrawn part of an unspent contribution. \[donor, amount, pot\]
+		DonationWithdrawn(AccountId, Balance, Balance),
+		/// The charity has been paused; donations and allocations are halted
+		Paused,
+		/// The charity has been unpaused; donations and allocations resume
+		Unpaused,
+		/// Donor has set up a recurring donation. \[donor, amount, interval\]
+		RecurringDonationScheduled(AccountId, Balance, BlockNumber),
+		/// Donor has cancelled their recurring donation. \[donor\]
+		RecurringDonationCancelled(AccountId),
+		/// A recurring donation fell due but could not be made. \[donor, amount\]
+		RecurringDonationSkipped(AccountId, Balance),
+		/// Part of a donation exceeded the pot's cap and was sent to the overflow destination.
+		/// \[donor, amount\]
+		DonationOverflowed(AccountId, Balance),
+		/// Sponsor has added funds to the matching pool. \[sponsor, amount, pool\]
+		MatchingFunded(AccountId, Balance, Balance),
+		/// Donor's donation has been matched from the matching pool. \[donor, amount\]
+		DonationMatched(AccountId, Balance),
+		/// Donor has given to a named cause. \[donor, cause, amount\]
+		DonatedToCause(AccountId, CauseName, Balance),
+		/// Charity has allocated funds set aside for a cause.
+		/// \[cause, dest, amount, remaining_cause_balance\]
+		FundsAllocatedFromCause(CauseName, AccountId, Balance, Balance),
+		/// The pot has moved to an account derived from a new ID. \[old, new, amount\]
+		PotMigrated(AccountId, AccountId, Balance),
+		/// Charity has allocated a grant to be paid out over time.
+		/// \[dest, total, per_block, start\]
+		VestingScheduled(AccountId, Balance, Balance, BlockNumber),
+		/// Part of a vesting grant has been paid. \[dest, amount, remaining\]
+		VestedChunkReleased(AccountId, Balance, Balance),
+		/// A vesting grant has been cancelled and its unreleased funds returned to the pot.
+		/// \[dest, unreleased\]
+		VestingCancelled(AccountId, Balance),
+		/// The charity has been wound down and the pot returned to donors. \[refunded, donors\]
+		CharityDissolved(Balance, u32),
+		/// A donation has beaten the largest made so far. \[donor, amount\]
+		NewRecordDonation(AccountId, Balance),
+		/// A donation has moved the donor on the `TopDonors` leaderboard. `None` means off the
+		/// board. \[donor, old_rank, new_rank\]
+		RankChanged(AccountId, Option<u32>, Option<u32>),
+		/// Donor has donated a non-native asset. \[donor, asset, amount, asset_pot\]
+		AssetDonationReceived(AccountId, AssetId, AssetBalance, AssetBalance),
+		/// Charity has allocated some of a non-native asset. \[dest, asset, amount, asset_pot\]
+		AssetFundsAllocated(AccountId, AssetId, AssetBalance, AssetBalance),
+		/// The per-call allocation limit has been changed. \[limit\]
+		AllocationLimitSet(Option<Balance>),
+		/// An allocation has been proposed. \[id, dest, amount, execute_at\]
+		AllocationQueued(u32, AccountId, Balance, BlockNumber),
+		/// A proposed allocation has been paid. \[id, dest, amount\]
+		AllocationExecuted(u32, AccountId, Balance),
+		/// A proposed allocation has been cancelled before it was paid. \[id\]
+		AllocationCancelled(u32),
+		/// The pot has reached one of the configured milestones for the first time. \[milestone\]
+		MilestoneReached(Balance),
+		/// Payer has donated on behalf of a beneficiary. \[payer, beneficiary, amount\]
+		DonatedOnBehalf(AccountId, AccountId, Balance),
+		/// An account has been added to the allowlist. \[who\]
+		AddedToAllowlist(AccountId),
+		/// An account has been removed from the allowlist. \[who\]
+		RemovedFromAllowlist(AccountId),
+		/// A donor has hidden themselves from, or returned to, the leaderboard.
+		/// \[who, anonymous\]
+		AnonymitySet(AccountId, bool),
+		/// Donations have been restricted to the allowlist, or opened up again. \[enabled\]
+		AllowlistEnabledSet(bool),
+		/// The pot has been topped up without crediting a donor. \[source, amount, new_pot_total\]
+		PotToppedUp(AccountId, Balance, Balance),
+		/// One donor's record has been folded into another's. \[from, into, amount\]
+		DonorRecordsMerged(AccountId, AccountId, Balance),
+		/// A donor's streak has reached one of the configured thresholds. \[donor, streak\]
+		StreakExtended(AccountId, u32),
+		/// The whole pot has been moved out in an emergency. \[dest, amount\]
+		PotSwept(AccountId, Balance),
+		/// A platform fee has been skimmed off a donation. \[donor, fee\]
+		DonationFeeCharged(AccountId, Balance),
+		/// The pallet's storage has been migrated. \[new_version\]
+		MigrationCompleted(StorageVersion),
+		/// Funds set aside for one cause have been moved to another. \[from, to, amount\]
+		CauseFundsTransferred(CauseName, CauseName, Balance),
+		/// The pot has dropped below `LowPotThreshold`. \[pot\]
+		PotRunningLow(Balance),
+		/// A cause has been closed and its funds returned to the general pot. \[cause, swept\]
+		CauseClosed(CauseName, Balance),
 	}
 );
 
+decl_error! {
+	pub enum Error for Module<T: Config<I>, I: Instance> {
+		/// The donor does not have enough free balance to make the donation
+		InsufficientFunds,
+		/// The pot does not hold enough funds to cover the allocation
+		InsufficientPot,
+		/// The caller has no recorded contribution to withdraw
+		NothingToWithdraw,
+		/// The caller tried to withdraw more than they have contributed
+		ExceedsContribution,
+		/// The donation is below the configured minimum
+		DonationTooSmall,
+		/// The donation is not a whole multiple of `DonationUnit`
+		NotUnitMultiple,
+		/// The memo attached to the donation is longer than allowed
+		MemoTooLong,
+		/// The cause's name is longer than allowed
+		CauseNameTooLong,
+		/// The cause does not hold enough funds to cover the allocation
+		InsufficientCauseFunds,
+		/// No more causes can be registered
+		TooManyCauses,
+		/// There is no cause with this name
+		UnknownCause,
+		/// The pot is already held under the requested ID
+		SamePotId,
+		/// The pot cannot be migrated while funds are locked for proposals
+		FundsLocked,
+		/// More funds were to be released than are locked
+		InsufficientLocked,
+		/// A vesting grant must have a non-zero total and per-block amount
+		InvalidVestingSchedule,
+		/// The batch holds more donations than allowed
+		BatchTooLarge,
+		/// The pot does not hold enough of the asset to cover the allocation
+		InsufficientAssetPot,
+		/// The allocation is larger than the current per-call limit
+		AllocationExceedsLimit,
+		/// There is no pending allocation with the given ID
+		NoSuchAllocation,
+		/// A tally would overflow
+		ArithmeticOverflow,
+		/// Only accounts on the allowlist may donate right now
+		DonorNotAllowed,
+		/// The donor has already made a donation with this nonce
+		DuplicateDonation,
+		/// This can only be done while the charity is paused
+		NotPaused,
+		/// The recipient already has a vesting grant in progress
+		VestingScheduleExists,
+		/// The recipient has no vesting grant
+		NoSuchVestingSchedule,
+		/// The allocation shares add up to more than the whole pot
+		InvalidShares,
+		/// The charity is paused and not accepting donations or allocations
+		PalletPaused,
+		/// A recurring donation must fall due at least once every so many blocks
+		ZeroInterval,
+		/// The caller has no recurring donation to cancel
+		NoRecurringDonation,
+		/// As many recurring donations as `MaxRecurringDonations` allows are already scheduled
+		TooManyRecurringDonations,
+		/// There are more donors on record than the `donor_count` given to `refund_all`
+		TooManyDonors,
+		/// Funds cannot be allocated back into the charity's own pot
+		CannotAllocateToSelf,
+		/// A donor's records cannot be merged into themselves
+		CannotMergeIntoSelf,
+		/// The allocation would leave less than `MinPotReserve` in the pot
+		WouldBreachReserve,
+		/// The donor donated too recently; they must wait out the `DonationCooldown`
+		DonationTooSoon,
+		/// The allocation would take this block's spending past `MaxSpendPerBlock`
+		BlockSpendLimitReached,
+		/// A queued allocation or vesting instalment is more than `MaxSpendPerBlock`, so no block
+		/// could ever pay it
+		ExceedsBlockSpendLimit,
+		/// An allocation can only be scheduled for a block that has not yet been reached
+		ScheduledInThePast,
+		/// The donation would take the donor past `PerDonorPeriodCap` for this period
+		PeriodCapExceeded,
+	}
+}
+
 decl_module! {
-	pub struct Module<T: Config> for enum Call where origin: T::Origin {
+	pub struct Module<T: Config<I>, I: Instance = DefaultInstance> for enum Call where origin: T::Origin {
 		fn deposit_event() = default;
 
+		type Error = Error<T, I>;
+
+		/// Bring storage written by an older version of the pallet up to date
+		fn on_runtime_upgrade() -> Weight {
+			if Self::storage_version() == StorageVersion::V1 {
+				Self::migrate_to_v2()
+			} else {
+				T::DbWeight::get().reads(1)
+			}
+		}
+
+		/// Renew the per-block spending allowance and make any recurring donations that fall due in
+		/// this block
+		///
+		/// A recurring donation falls due in every block that is a multiple of its interval. If the
+		/// donor can't cover it, that instalment is skipped but the schedule stays in place. Every
+		/// schedule is read in every block, which `MaxRecurringDonations` keeps within bounds, and
+		/// the weight returned counts each one read and each donation made.
+		fn on_initialize(now: T::BlockNumber) -> Weight {
+			// A fresh block starts with a fresh spending allowance
+			<SpentThisBlock<T, I>>::kill();
+			let mut weight: Weight = T::DbWeight::get().writes(1);
+			if !T::LowPotThreshold::get().is_zero() {
+				// Paid for here, but checked in `on_finalize`
+				weight = weight.saturating_add(T::DbWeight::get().reads_writes(2, 1));
+			}
+			if Self::snapshot_due(now) {
+				// Paid for here, but taken in `on_finalize`
+				weight = weight.saturating_add(T::DbWeight::get().reads_writes(3, 3));
+			}
+			for (donor, (amount, interval)) in <RecurringDonations<T, I>>::iter() {
+				weight = weight.saturating_add(T::DbWeight::get().reads(1));
+				if !(now % interval).is_zero() {
+					continue;
+				}
+
+				weight = weight.saturating_add(T::WeightInfo::donate(0));
+				if Self::do_donate(&donor, amount, Default::default()).is_err() {
+					Self::deposit_event(RawEvent::RecurringDonationSkipped(donor, amount));
+				}
+			}
+
+			if Self::is_paused() {
+				return weight;
+			}
+			let schedules: Vec<_> = <VestingSchedules<T, I>>::iter().collect();
+			for (dest, schedule) in schedules {
+				weight = weight.saturating_add(T::DbWeight::get().reads(1));
+				if now < schedule.start {
+					continue;
+				}
+
+				weight = weight.saturating_add(T::WeightInfo::allocate());
+				let chunk = schedule.per_block.min(schedule.remaining);
+				if Self::pay_locked(&dest, chunk).is_err() {
+					// Try again next block
+					continue;
+				}
+				let remaining = schedule.remaining.saturating_sub(chunk);
+				if remaining.is_zero() {
+					<VestingSchedules<T, I>>::remove(&dest);
+				} else {
+					<VestingSchedules<T, I>>::insert(&dest, VestingSchedule { remaining, ..schedule });
+				}
+				Self::deposit_event(RawEvent::VestedChunkReleased(dest, chunk, remaining));
+			}
+
+			let pending: Vec<_> = <PendingAllocations<T, I>>::iter().collect();
+			for (id, allocation) in pending {
+				weight = weight.saturating_add(T::DbWeight::get().reads(1));
+				if now < allocation.execute_at {
+					continue;
+				}
+
+				weight = weight.saturating_add(T::WeightInfo::allocate());
+				if Self::pay_locked(&allocation.dest, allocation.amount).is_err() {
+					// Try again next block
+					continue;
+				}
+				<PendingAllocations<T, I>>::remove(id);
+				Self::deposit_event(RawEvent::AllocationExecuted(
+					id,
+					allocation.dest,
+					allocation.amount,
+				));
+			}
+			weight
+		}
+
+		/// Warn if the pot is running low, and record the pot balance if a snapshot is due, pruning
+		/// the oldest once there are too many
+		fn on_finalize(now: T::BlockNumber) {
+			Self::check_pot_level();
+			if !Self::snapshot_due(now) {
+				return;
+			}
+
+			<PotHistory<T, I>>::insert(now, Self::pot());
+			<SnapshotBlocks<T, I>>::mutate(|blocks| {
+				blocks.push(now);
+				let excess = blocks.len().saturating_sub(T::MaxSnapshots::get() as usize);
+				for old in blocks.drain(..excess) {
+					<PotHistory<T, I>>::remove(old);
+				}
+			});
+		}
+
 		/// Donate some funds to the charity
-		#[weight = 10_000]
+		///
+		/// The donor may attach a short memo such as a dedication. Pass an empty memo to donate
+		/// without one. The weight grows with the memo's length, and a memo longer than
+		/// `MaxMemoLength` is rejected before anything else is done.
+		///
+		/// A relayer that may submit the same donation twice can pass a `nonce` of its choosing;
+		/// a second donation from the same donor with the same nonce fails with
+		/// `DuplicateDonation`. This is unrelated to the account's transaction nonce.
+		#[weight = T::WeightInfo::donate(memo.len() as u32)]
 		fn donate(
 			origin,
-			amount: BalanceOf<T>
+			amount: BalanceOf<T, I>,
+			memo: Vec<u8>,
+			nonce: Option<u32>,
+		) -> DispatchResult {
+			let donor = ensure_signed(origin)?;
+			let memo: MemoOf<T, I> = memo.try_into().map_err(|_| Error::<T, I>::MemoTooLong)?;
+			if let Some(nonce) = nonce {
+				ensure!(!<SeenNonces<T, I>>::get(&donor, nonce), Error::<T, I>::DuplicateDonation);
+			}
+
+			Self::do_donate(&donor, amount, memo)?;
+
+			if let Some(nonce) = nonce {
+				<SeenNonces<T, I>>::insert(&donor, nonce, true);
+			}
+			Ok(())
+		}
+
+		/// Donate everything the caller can spare
+		///
+		/// The caller keeps exactly the existential deposit, so their account stays alive. Where
+		/// `DonationUnit` is set, the amount is rounded down to a whole number of units. Otherwise
+		/// this is an ordinary donation with an empty memo.
+		#[weight = T::WeightInfo::donate_all()]
+		fn donate_all(origin) -> DispatchResult {
+			let donor = ensure_signed(origin)?;
+
+			let spare = T::Currency::free_balance(&donor)
+				.saturating_sub(T::Currency::minimum_balance());
+			let unit = T::DonationUnit::get();
+			let amount = if unit.is_zero() { spare } else { spare - spare % unit };
+
+			Self::do_donate(&donor, amount, Default::default())?;
+			Ok(())
+		}
+
+		/// Donate on behalf of another account
+		///
+		/// The caller pays, but the donation is credited to `beneficiary` exactly as if they had
+		/// made it themselves. This suits payment processors and custodians giving for their users.
+		#[weight = T::WeightInfo::donate_on_behalf()]
+		fn donate_on_behalf(
+			origin,
+			beneficiary: T::AccountId,
+			amount: BalanceOf<T, I>,
+		) -> DispatchResult {
+			let payer = ensure_signed(origin)?;
+
+			let donated = Self::do_donate_for(&payer, &beneficiary, amount, Default::default())?;
+
+			Self::deposit_event(RawEvent::DonatedOnBehalf(payer, beneficiary, donated));
+			Ok(())
+		}
+
+		/// Donate through the charity straight to a recipient
+		///
+		/// The donation is recorded and receipted as usual, then whatever reached the pot is
+		/// passed on to `dest` at once. Donors direct their own funds, so no special origin is
+		/// needed.
+		#[weight = T::WeightInfo::donate_and_allocate()]
+		#[transactional]
+		fn donate_and_allocate(
+			origin,
+			dest: T::AccountId,
+			amount: BalanceOf<T, I>,
+		) -> DispatchResult {
+			let donor = ensure_signed(origin)?;
+			ensure!(dest != Self::account_id(), Error::<T, I>::CannotAllocateToSelf);
+
+			let donated = Self::do_donate(&donor, amount, Default::default())?;
+			let paid = Self::pay_allocation_with_fee(
+				&dest,
+				donated,
+				T::AllocationExistenceRequirement::get(),
+			)?;
+
+			Self::deposit_event(RawEvent::FundsAllocated(dest.clone(), paid, Self::pot()));
+			Ok(())
+		}
+
+		/// Donate funds to a named cause
+		///
+		/// The funds join the shared pot, but are counted towards the cause so that they can later
+		/// be allocated through `allocate_from_cause`.
+		#[weight = T::WeightInfo::donate_to_cause()]
+		#[transactional]
+		fn donate_to_cause(
+			origin,
+			cause: Vec<u8>,
+			amount: BalanceOf<T, I>,
 		) -> DispatchResult {
 			let donor = ensure_signed(origin)?;
+			let cause = Self::cause_name(cause)?;
+
+			let donated = Self::do_donate(&donor, amount, Default::default())?;
+			Self::credit_cause(&cause, donated)?;
 
-			T::Currency::transfer(&donor, &Self::account_id(), amount, AllowDeath)
-				.map_err(|_| DispatchError::Other("Can't make donation"))?;
+			Self::deposit_event(RawEvent::DonatedToCause(donor, cause, donated));
+			Ok(())
+		}
+
+		/// Donate to several named causes at once
+		///
+		/// The amounts are summed and moved from the donor as a single donation, then credited to
+		/// each cause in turn. If the pot's cap means only part of the sum is donated, the causes
+		/// listed first are credited first.
+		#[weight = T::WeightInfo::donate_many(donations.len() as u32)]
+		#[transactional]
+		fn donate_many(
+			origin,
+			donations: Vec<(Vec<u8>, BalanceOf<T, I>)>,
+		) -> DispatchResult {
+			let donor = ensure_signed(origin)?;
+			ensure!(
+				donations.len() <= T::MaxBatchSize::get() as usize,
+				Error::<T, I>::BatchTooLarge
+			);
+			let donations = donations
+				.into_iter()
+				.map(|(cause, amount)| Ok((Self::cause_name(cause)?, amount)))
+				.collect::<Result<Vec<_>, DispatchError>>()?;
 
-			Self::deposit_event(RawEvent::DonationReceived(donor, amount, Self::pot()));
+			let total = donations
+				.iter()
+				.fold(Zero::zero(), |sum: BalanceOf<T, I>, (_, amount)| sum.saturating_add(*amount));
+			let mut left = Self::do_donate(&donor, total, Default::default())?;
+			for (cause, amount) in donations {
+				let credited = amount.min(left);
+				left = left.saturating_sub(credited);
+				Self::credit_cause(&cause, credited)?;
+				Self::deposit_event(RawEvent::DonatedToCause(donor.clone(), cause, credited));
+			}
 			Ok(())
 		}
 
 		/// Allocate the Charity's funds
 		///
-		/// Take funds from the Charity's pot and send them somewhere. This call requires root origin,
-		/// which means it must come from a governance mechanism such as Substrate's Democracy pallet.
-		#[weight = 10_000]
+		/// Take funds from the Charity's pot and send them somewhere. This call requires
+		/// `AllocationOrigin`, which the runtime may set to root, a council majority, or any other
+		/// governance mechanism such as Substrate's Democracy pallet.
+		///
+		/// Allocating nothing does nothing, and an allocation to the pot itself is rejected. Both
+		/// are caught before any storage is read, so only `allocate_noop` is charged for them.
+		///
+		/// The `AllocationFee` share of `amount` goes to `OperationsAccount` and `dest` receives
+		/// the rest, so the pot pays out exactly `amount` either way.
+		#[weight = T::WeightInfo::allocate()]
 		fn allocate(
 			origin,
 			dest: T::AccountId,
-			amount: BalanceOf<T>,
+			amount: BalanceOf<T, I>,
+		) -> DispatchResultWithPostInfo {
+			T::AllocationOrigin::ensure_origin(origin)?;
+			let noop = T::WeightInfo::allocate_noop();
+			if amount.is_zero() {
+				return Ok(Some(noop).into());
+			}
+			ensure!(
+				dest != Self::account_id(),
+				Error::<T, I>::CannotAllocateToSelf.with_weight(noop)
+			);
+			if let Err(e) = Self::ensure_allocatable(amount) {
+				if e == Error::<T, I>::InsufficientPot.into() {
+					// Left behind by the failed call, so tooling can tell how far short the pot fell
+					let available = Self::available_to_allocate();
+					Self::deposit_event(RawEvent::AllocationShortfall(
+						amount,
+						available,
+						amount.saturating_sub(available),
+					));
+				}
+				return Err(e.into());
+			}
+
+			// Make the transfer requested, less the operations fee
+			let paid =
+				Self::pay_governed_allocation(&dest, amount, T::AllocationExistenceRequirement::get())?;
+
+			Self::deposit_event(RawEvent::FundsAllocated(dest.clone(), paid, Self::pot()));
+			Ok(().into())
+		}
+
+		/// Allocate everything the pot can pay out to a single recipient
+		///
+		/// With `keep_alive` set, the pot keeps its existential deposit and stays open for further
+		/// donations. Without it, the whole allocatable pot is paid out and, unless funds are
+		/// locked or `UseReserved` is set, the pot account is reaped. Either way, `MinPotReserve`
+		/// stays behind, and funds locked for vesting or queued allocations are never touched. The
+		/// amount must respect the allocation limit. Requires root origin.
+		#[weight = T::WeightInfo::allocate_all()]
+		fn allocate_all(origin, dest: T::AccountId, keep_alive: bool) -> DispatchResult {
+			ensure_root(origin)?;
+			Self::ensure_not_paused()?;
+			ensure!(dest != Self::account_id(), Error::<T, I>::CannotAllocateToSelf);
+
+			let (amount, existence) = if keep_alive {
+				(Self::spendable_pot(), KeepAlive)
+			} else {
+				(Self::available_to_allocate(), AllowDeath)
+			};
+			let amount = amount.saturating_sub(T::MinPotReserve::get());
+			ensure!(!amount.is_zero(), Error::<T, I>::InsufficientPot);
+			Self::ensure_within_allocation_limit(amount)?;
+			Self::ensure_keeps_reserve(amount)?;
+
+			let paid = Self::pay_governed_allocation(&dest, amount, existence)?;
+
+			Self::deposit_event(RawEvent::FundsAllocated(dest.clone(), paid, Self::pot()));
+			Ok(())
+		}
+
+		/// Allocate a share of the pot to a single recipient
+		///
+		/// The amount is worked out from the pot as it stands when the call executes, so a
+		/// proposal can't go stale if the pot shrinks while it waits. The amount must respect the
+		/// allocation limit and `MinPotReserve`. Requires root origin.
+		#[weight = T::WeightInfo::allocate_percentage()]
+		fn allocate_percentage(origin, dest: T::AccountId, share: Perbill) -> DispatchResult {
+			ensure_root(origin)?;
+			Self::ensure_not_paused()?;
+
+			let amount = share * Self::available_to_allocate();
+			ensure!(!amount.is_zero(), Error::<T, I>::InsufficientPot);
+			let paid = Self::try_allocate(&dest, amount)?;
+
+			Self::deposit_event(RawEvent::FundsAllocated(dest.clone(), paid, Self::pot()));
+			Ok(())
+		}
+
+		/// Allocate funds to several recipients at once
+		///
+		/// Each payment is attempted on its own, in order: one that fails emits `AllocationFailed`
+		/// and is skipped without undoing the others. Each payment must respect the allocation
+		/// limit. Requires root origin.
+		#[weight = T::WeightInfo::allocate_batch(payments.len() as u32)]
+		fn allocate_batch(
+			origin,
+			payments: Vec<(T::AccountId, BalanceOf<T, I>)>,
 		) -> DispatchResult {
 			ensure_root(origin)?;
+			Self::ensure_not_paused()?;
+			ensure!(
+				payments.len() <= T::MaxBatchSize::get() as usize,
+				Error::<T, I>::BatchTooLarge
+			);
 
-			// Make the transfer requested
-			T::Currency::transfer(
-				&Self::account_id(),
-				&dest,
-				amount,
-				AllowDeath,
-			).map_err(|_| DispatchError::Other("Can't make allocation"))?;
+			for (dest, amount) in payments {
+				match Self::try_allocate(&dest, amount) {
+					Ok(paid) => Self::deposit_event(RawEvent::FundsAllocated(dest, paid, Self::pot())),
+					Err(_) => Self::deposit_event(RawEvent::AllocationFailed(dest, amount)),
+				}
+			}
+			Ok(())
+		}
+
+		/// Donate some of a non-native asset to the charity
+		///
+		/// The asset is moved to the pot account and tracked separately from the native pot.
+		#[weight = T::WeightInfo::donate_asset()]
+		fn donate_asset(
+			origin,
+			asset: AssetIdOf<T, I>,
+			amount: AssetBalanceOf<T, I>,
+		) -> DispatchResult {
+			let donor = ensure_signed(origin)?;
+			Self::ensure_not_paused()?;
+			Self::ensure_allowed(&donor)?;
 
-			//TODO what about errors here??
+			T::Assets::transfer(asset, &donor, &Self::account_id(), amount)
+				.map_err(|_| Error::<T, I>::InsufficientFunds)?;
+			let pot = <AssetPot<T, I>>::mutate(asset, |pot| {
+				*pot = pot.saturating_add(amount);
+				*pot
+			});
 
-			Self::deposit_event(RawEvent::FundsAllocated(dest, amount, Self::pot()));
+			Self::deposit_event(RawEvent::AssetDonationReceived(donor, asset, amount, pot));
 			Ok(())
 		}
-	}
-}
 
-impl<T: Config> Module<T> {
-	/// The account ID that holds the Charity's funds
-	pub fn account_id() -> T::AccountId {
-		PALLET_ID.into_account()
-	}
+		/// Allocate some of a non-native asset held by the charity
+		///
+		/// The asset counterpart of `allocate`. Requires root origin.
+		#[weight = T::WeightInfo::allocate_asset()]
+		fn allocate_asset(
+			origin,
+			asset: AssetIdOf<T, I>,
+			dest: T::AccountId,
+			amount: AssetBalanceOf<T, I>,
+		) -> DispatchResult {
+			ensure_root(origin)?;
+			Self::ensure_not_paused()?;
+			let pot = Self::asset_pot(asset);
+			ensure!(pot >= amount, Error::<T, I>::InsufficientAssetPot);
 
-	/// The Charity's balance
-	fn pot() -> BalanceOf<T> {
-		T::Currency::free_balance(&Self::account_id())
-	}
-}
+			T::Assets::transfer(asset, &Self::account_id(), &dest, amount)?;
+			let remaining = pot.saturating_sub(amount);
+			if remaining.is_zero() {
+				<AssetPot<T, I>>::remove(asset);
+			} else {
+				<AssetPot<T, I>>::insert(asset, remaining);
+			}
 
-// This implementation allows the charity to be the recipient of funds that are burned elsewhere in
-// the runtime. For eample, it could be transaction fees, consensus-related slashing, or burns that
-// align incentives in other pallets.
-impl<T: Config> OnUnbalanced<NegativeImbalanceOf<T>> for Module<T> {
-	fn on_nonzero_unbalanced(amount: NegativeImbalanceOf<T>) {
-		let numeric_amount = amount.peek();
+			Self::deposit_event(RawEvent::AssetFundsAllocated(dest, asset, amount, remaining));
+			Ok(())
+		}
 
-		// Must resolve into existing but better to be safe.
-		let _ = T::Currency::resolve_creating(&Self::account_id(), amount);
+		/// Allocate funds set aside for a named cause
+		///
+		/// Works like `allocate`, but the amount must also be covered by the cause's tally, which it
+		/// is deducted from. Requires root origin.
+		#[weight = T::WeightInfo::allocate_from_cause()]
+		fn allocate_from_cause(
+			origin,
+			cause: Vec<u8>,
+			dest: T::AccountId,
+			amount: BalanceOf<T, I>,
+		) -> DispatchResult {
+			ensure_root(origin)?;
+			Self::ensure_not_paused()?;
+			ensure!(dest != Self::account_id(), Error::<T, I>::CannotAllocateToSelf);
+			let cause = Self::cause_name(cause)?;
+			let tally = Self::cause_balance(&cause);
+			ensure!(tally >= amount, Error::<T, I>::InsufficientCauseFunds);
+			Self::ensure_within_allocation_limit(amount)?;
+			ensure!(Self::available_to_allocate() >= amount, Error::<T, I>::InsufficientPot);
+			Self::ensure_keeps_reserve(amount)?;
+
+			let paid =
+				Self::pay_governed_allocation(&dest, amount, T::AllocationExistenceRequirement::get())?;
+			let remaining = tally.saturating_sub(amount);
+			if remaining.is_zero() {
+				<Causes<T, I>>::remove(&cause);
+			} else {
+				<Causes<T, I>>::insert(&cause, remaining);
+			}
+
+			Self::deposit_event(RawEvent::FundsAllocatedFromCause(cause, dest, paid, remaining));
+			Ok(())
+		}
+
+		/// Move funds set aside for one cause to another
+		///
+		/// Both causes share the pot, so no tokens move; only the tallies change. `to` is
+		/// registered if it is new. Requires root origin.
+		#[weight = T::WeightInfo::transfer_between_causes()]
+		#[transactional]
+		fn transfer_between_causes(
+			origin,
+			from: Vec<u8>,
+			to: Vec<u8>,
+			amount: BalanceOf<T, I>,
+		) -> DispatchResult {
+			ensure_root(origin)?;
+			let from = Self::cause_name(from)?;
+			let to = Self::cause_name(to)?;
+			let tally = Self::cause_balance(&from);
+			ensure!(tally >= amount, Error::<T, I>::InsufficientCauseFunds);
+
+			let remaining = tally.saturating_sub(amount);
+			if remaining.is_zero() {
+				<Causes<T, I>>::remove(&from);
+			} else {
+				<Causes<T, I>>::insert(&from, remaining);
+			}
+			Self::credit_cause(&to, amount)?;
+
+			Self::deposit_event(RawEvent::CauseFundsTransferred(from, to, amount));
+			Ok(())
+		}
+
+		/// Close a cause, folding whatever is set aside for it back into the general funds
+		///
+		/// The funds stay in the pot; only the earmark goes. The cause is also dropped from
+		/// `CauseList`, so a later donation registers it afresh. Requires root origin.
+		#[weight = T::WeightInfo::close_cause()]
+		fn close_cause(origin, cause: Vec<u8>) -> DispatchResult {
+			ensure_root(origin)?;
+			let cause = Self::cause_name(cause)?;
+			let mut list = Self::cause_list();
+			let position = list.iter().position(|known| known == &cause);
+			ensure!(
+				position.is_some() || <Causes<T, I>>::contains_key(&cause),
+				Error::<T, I>::UnknownCause
+			);
+
+			let swept = <Causes<T, I>>::take(&cause);
+			if let Some(position) = position {
+				list.remove(position);
+				<CauseList<T, I>>::put(list);
+			}
+
+			Self::deposit_event(RawEvent::CauseClosed(cause, swept));
+			Ok(())
+		}
+
+		/// Allocate a grant that is paid out of the pot over time
+		///
+		/// From block `start`, `per_block` is paid to `dest` each block until `total` has been paid;
+		/// the last payment may be smaller. The whole grant is locked in the pot up front, and each
+		/// payment must fit within `MaxSpendPerBlock`. Requires root origin.
+		#[weight = T::WeightInfo::allocate_with_vesting()]
+		fn allocate_with_vesting(
+			origin,
+			dest: T::AccountId,
+			total: BalanceOf<T, I>,
+			per_block: BalanceOf<T, I>,
+			start: T::BlockNumber,
+		) -> DispatchResult {
+			ensure_root(origin)?;
+			Self::ensure_not_paused()?;
+			ensure!(dest != Self::account_id(), Error::<T, I>::CannotAllocateToSelf);
+			ensure!(
+				!total.is_zero() && !per_block.is_zero(),
+				Error::<T, I>::InvalidVestingSchedule
+			);
+			ensure!(
+				!<VestingSchedules<T, I>>::contains_key(&dest),
+				Error::<T, I>::VestingScheduleExists
+			);
+			Self::ensure_within_allocation_limit(total)?;
+			Self::ensure_payable_in_one_block(per_block.min(total))?;
+
+			Self::lock_for_proposal(total)?;
+			<VestingSchedules<T, I>>::insert(&dest, VestingSchedule { remaining: total, per_block, start });
+
+			Self::deposit_event(RawEvent::VestingScheduled(dest, total, per_block, start));
+			Ok(())
+		}
+
+		/// Cancel `dest`'s vesting grant, returning what has not yet been released to the pot
+		///
+		/// Whatever has already been paid stays with the recipient. Requires root origin.
+		#[weight = T::WeightInfo::cancel_vesting()]
+		fn cancel_vesting(origin, dest: T::AccountId) -> DispatchResult {
+			ensure_root(origin)?;
+			let schedule =
+				Self::vesting_schedule_of(&dest).ok_or(Error::<T, I>::NoSuchVestingSchedule)?;
+
+			Self::release_lock(schedule.remaining)?;
+			<VestingSchedules<T, I>>::remove(&dest);
+
+			Self::deposit_event(RawEvent::VestingCancelled(dest, schedule.remaining));
+			Ok(())
+		}
 
-		Self::deposit_event(RawEvent::ImbalanceAbsorbed(numeric_amount, Self::pot()));
+		/// Have the runtime's scheduler allocate `amount` to `dest` at block `at`
+		///
+		/// The scheduler dispatches `allocate` with root origin, so `AllocationOrigin` must accept
+		/// root. Nothing is set aside in the meantime: the allocation is checked when it runs and
+		/// fails then if the pot can't cover it. Requires root origin.
+		#[weight = T::WeightInfo::schedule_allocation()]
+		fn schedule_allocation(
+			origin,
+			dest: T::AccountId,
+			amount: BalanceOf<T, I>,
+			at: T::BlockNumber,
+		) -> DispatchResult {
+			ensure_root(origin)?;
+			ensure!(dest != Self::account_id(), Error::<T, I>::CannotAllocateToSelf);
+			ensure!(
+				at > <frame_system::Module<T>>::block_number(),
+				Error::<T, I>::ScheduledInThePast
+			);
+
+			let call = Call::<T, I>::allocate(dest.clone(), amount);
+			T::Scheduler::schedule(
+				DispatchTime::At(at),
+				None,
+				HARD_DEADLINE,
+				frame_system::RawOrigin::Root.into(),
+				call.into(),
+			)?;
+
+			Self::deposit_event(RawEvent::AllocationScheduled(dest, amount, at));
+			Ok(())
+		}
+
+		/// Propose an allocation that is paid after `AllocationDelay` blocks
+		///
+		/// The funds are locked straight away, and the allocation can be vetoed with
+		/// `cancel_allocation` until it is paid. It is paid in a single block, so it must fit
+		/// within `MaxSpendPerBlock`. Requires root origin.
+		#[weight = T::WeightInfo::propose_allocation()]
+		fn propose_allocation(
+			origin,
+			dest: T::AccountId,
+			amount: BalanceOf<T, I>,
+		) -> DispatchResult {
+			ensure_root(origin)?;
+			Self::ensure_not_paused()?;
+			ensure!(dest != Self::account_id(), Error::<T, I>::CannotAllocateToSelf);
+			Self::ensure_within_allocation_limit(amount)?;
+			Self::ensure_payable_in_one_block(amount)?;
+
+			Self::lock_for_proposal(amount)?;
+			let id = <NextAllocationId<I>>::mutate(|next| {
+				let id = *next;
+				*next = next.wrapping_add(1);
+				id
+			});
+			let execute_at =
+				<frame_system::Module<T>>::block_number().saturating_add(T::AllocationDelay::get());
+			<PendingAllocations<T, I>>::insert(id, PendingAllocation {
+				dest: dest.clone(),
+				amount,
+				execute_at,
+			});
+
+			Self::deposit_event(RawEvent::AllocationQueued(id, dest, amount, execute_at));
+			Ok(())
+		}
+
+		/// Cancel a proposed allocation before it is paid, returning its funds to the pot
+		#[weight = T::WeightInfo::cancel_allocation()]
+		fn cancel_allocation(origin, id: u32) -> DispatchResult {
+			ensure_root(origin)?;
+			let allocation = Self::pending_allocation(id).ok_or(Error::<T, I>::NoSuchAllocation)?;
+
+			Self::release_lock(allocation.amount)?;
+			<PendingAllocations<T, I>>::remove(id);
+
+			Self::deposit_event(RawEvent::AllocationCancelled(id));
+			Ok(())
+		}
+
+		/// Allocate the Charity's funds to several recipients at once
+		///
+		/// Each recipient receives their share of the pot as it stands when the call begins,
+		/// rounded down. The shares may not add up to more than the whole pot, and whatever they
+		/// leave unclaimed stays in the pot. The dust from rounding is handled by `remainder`. At
+		/// most `MaxBatchSize` recipients may be paid at once, every payout must respect the
+		/// allocation limit, and together they must leave `MinPotReserve`, or nothing is paid.
+		/// Requires root origin, unlike `allocate`, which takes `AllocationOrigin`.
+		#[weight = T::WeightInfo::allocate_proportional(recipients.len() as u32)]
+		#[transactional]
+		fn allocate_proportional(
+			origin,
+			recipients: Vec<(T::AccountId, Perbill)>,
+			remainder: RemainderPolicy,
+		) -> DispatchResult {
+			ensure_root(origin)?;
+			ensure!(
+				recipients.len() <= T::MaxBatchSize::get() as usize,
+				Error::<T, I>::BatchTooLarge
+			);
+			Self::ensure_not_paused()?;
+			let pot_account = Self::account_id();
+			ensure!(
+				recipients.iter().all(|(dest, _)| *dest != pot_account),
+				Error::<T, I>::CannotAllocateToSelf
+			);
+
+			let total_parts: u64 = recipients.iter().map(|(_, share)| share.deconstruct() as u64).sum();
+			ensure!(total_parts <= Perbill::one().deconstruct() as u64, Error::<T, I>::InvalidShares);
+
+			let pot = Self::pot();
+			let mut payouts = recipients
+				.into_iter()
+				.map(|(dest, share)| (dest, share.mul_floor(pot)))
+				.collect::<Vec<_>>();
+			let paid = payouts
+				.iter()
+				.fold(Zero::zero(), |sum: BalanceOf<T, I>, (_, amount)| sum.saturating_add(*amount));
+			// Rounded down, so the dust never takes more than the shares add up to
+			let dust = Perbill::from_parts(total_parts as u32).mul_floor(pot).saturating_sub(paid);
+			let receiver = match remainder {
+				RemainderPolicy::KeepInPot => None,
+				RemainderPolicy::GiveToFirst => payouts.first_mut(),
+				RemainderPolicy::GiveToLast => payouts.last_mut(),
+			};
+			if let Some((_, amount)) = receiver {
+				*amount = amount.saturating_add(dust);
+			}
+			let mut total: BalanceOf<T, I> = Zero::zero();
+			for (_, amount) in &payouts {
+				Self::ensure_within_allocation_limit(*amount)?;
+				total = total.saturating_add(*amount);
+			}
+			Self::ensure_keeps_reserve(total)?;
+
+			for (dest, amount) in payouts {
+				let paid = Self::pay_governed_allocation(
+					&dest,
+					amount,
+					T::AllocationExistenceRequirement::get(),
+				)?;
+				Self::deposit_event(RawEvent::FundsAllocated(dest, paid, Self::pot()));
+			}
+			Ok(())
+		}
+
+		/// Withdraw part or all of a previous donation
+		///
+		/// Donors may reclaim contributions as long as the pot still holds the funds. The pot always
+		/// keeps its existential deposit, so a withdrawal can never reap the pallet account.
+		#[weight = T::WeightInfo::withdraw_donation()]
+		fn withdraw_donation(
+			origin,
+			amount: BalanceOf<T, I>,
+		) -> DispatchResult {
+			let donor = ensure_signed(origin)?;
+
+			let given = Self::donation_of(&donor);
+			ensure!(!given.is_zero(), Error::<T, I>::NothingToWithdraw);
+			ensure!(amount <= given, Error::<T, I>::ExceedsContribution);
+			ensure!(amount <= Self::spendable_pot(), Error::<T, I>::InsufficientPot);
+
+			Self::pay_from_pot(&donor, amount, KeepAlive)?;
+
+			let remaining = given.saturating_sub(amount);
+			if remaining.is_zero() {
+				<Donations<T, I>>::remove(&donor);
+				<DistinctDonors<I>>::mutate(|count| *count = count.saturating_sub(1));
+			} else {
+				<Donations<T, I>>::insert(&donor, remaining);
+			}
+			<TotalDonated<T, I>>::mutate(|total| *total = total.saturating_sub(amount));
+			Self::update_top_donors(&donor, remaining);
+
+			Self::deposit_event(RawEvent::DonationWithdrawn(donor, amount, Self::pot()));
+			Ok(())
+		}
+
+		/// Return the pot to donors and clear their records
+		///
+		/// Each donor receives a share of the spendable pot in proportion to their recorded
+		/// contribution. Any dust left by rounding, and any refund that cannot be paid, stays in the
+		/// pot. This iterates over every donor, so it is meant for a one-off governance decision to
+		/// wind the charity down. Requires root origin.
+		///
+		/// The call is weighed by `donor_count`, which must be at least `DistinctDonors`; a
+		/// proposal drawn up before more donors arrived fails with `TooManyDonors`.
+		#[weight = T::WeightInfo::refund_all(*donor_count)]
+		fn refund_all(origin, donor_count: u32) -> DispatchResult {
+			ensure_root(origin)?;
+			ensure!(Self::distinct_donors() <= donor_count, Error::<T, I>::TooManyDonors);
+
+			let pot = Self::spendable_pot();
+			let total = Self::recorded_donations();
+			let donations: Vec<_> = <Donations<T, I>>::drain().collect();
+			<TotalDonated<T, I>>::kill();
+			<TopDonors<T, I>>::kill();
+			<DistinctDonors<I>>::kill();
+
+			let mut refunded: BalanceOf<T, I> = Zero::zero();
+			let mut donors = 0u32;
+			for (donor, given) in donations {
+				let share: BalanceOf<T, I> = multiply_by_rational(
+					pot.saturated_into(),
+					given.saturated_into(),
+					total.saturated_into(),
+				)
+				.map(|share| share.saturated_into())
+				.unwrap_or_else(|_| Zero::zero());
+				if !share.is_zero() && Self::pay_from_pot(&donor, share, KeepAlive).is_ok() {
+					refunded = refunded.saturating_add(share);
+					donors = donors.saturating_add(1);
+				}
+			}
+
+			Self::deposit_event(RawEvent::CharityDissolved(refunded, donors));
+			Ok(())
+		}
+
+		/// Set up a recurring donation
+		///
+		/// From now on, `amount` is donated in every block that is a multiple of `interval`. Calling
+		/// this again replaces the donor's existing schedule.
+		///
+		/// A new schedule reserves `RecurringDonationDeposit` from the donor until it is cancelled,
+		/// and fails with `TooManyRecurringDonations` once `MaxRecurringDonations` are in place.
+		#[weight = T::WeightInfo::schedule_donation()]
+		fn schedule_donation(
+			origin,
+			amount: BalanceOf<T, I>,
+			interval: T::BlockNumber,
+		) -> DispatchResult {
+			let donor = ensure_signed(origin)?;
+			ensure!(!interval.is_zero(), Error::<T, I>::ZeroInterval);
+			ensure!(amount >= T::MinimumDonation::get(), Error::<T, I>::DonationTooSmall);
+			Self::ensure_whole_units(amount)?;
+
+			if !<RecurringDonations<T, I>>::contains_key(&donor) {
+				let count = Self::recurring_donation_count();
+				ensure!(
+					count < T::MaxRecurringDonations::get(),
+					Error::<T, I>::TooManyRecurringDonations
+				);
+				let deposit = T::RecurringDonationDeposit::get();
+				T::Currency::reserve(&donor, deposit).map_err(|_| Error::<T, I>::InsufficientFunds)?;
+				<RecurringDonationDeposits<T, I>>::insert(&donor, deposit);
+				<RecurringDonationCount<I>>::put(count + 1);
+			}
+			<RecurringDonations<T, I>>::insert(&donor, (amount, interval));
+
+			Self::deposit_event(RawEvent::RecurringDonationScheduled(donor, amount, interval));
+			Ok(())
+		}
+
+		/// Add funds to the matching pool
+		///
+		/// Until the pool runs dry, every donation is matched by an equal amount from it.
+		#[weight = T::WeightInfo::fund_matching()]
+		fn fund_matching(origin, amount: BalanceOf<T, I>) -> DispatchResult {
+			let sponsor = ensure_signed(origin)?;
+
+			T::Currency::transfer(&sponsor, &Self::matching_account_id(), amount, AllowDeath)
+				.map_err(|_| Error::<T, I>::InsufficientFunds)?;
+			let pool = <MatchingPool<T, I>>::mutate(|pool| {
+				*pool = pool.saturating_add(amount);
+				*pool
+			});
+
+			Self::deposit_event(RawEvent::MatchingFunded(sponsor, amount, pool));
+			Ok(())
+		}
+
+		/// Top up the pot directly from another account
+		///
+		/// Meant for trusted transfers, such as bridging funds from another treasury. Unlike a
+		/// donation, the top-up is not credited to `source`: no donor records, leaderboard places or
+		/// matching funds. Requires root origin.
+		#[weight = T::WeightInfo::fund_pot_from_account()]
+		#[transactional]
+		fn fund_pot_from_account(
+			origin,
+			source: T::AccountId,
+			amount: BalanceOf<T, I>,
+		) -> DispatchResult {
+			ensure_root(origin)?;
+
+			Self::deposit_into_pot(&source, amount).map_err(|_| Error::<T, I>::InsufficientFunds)?;
+
+			Self::deposit_event(RawEvent::PotToppedUp(source, amount, Self::pot()));
+			Ok(())
+		}
+
+		/// Donate from another account without its signature
+		///
+		/// Meant for integration tests and airdrops. Unlike `fund_pot_from_account`, this is a
+		/// donation in every respect: it is credited to `from` and counts towards the leaderboard.
+		/// Requires root origin.
+		#[weight = T::WeightInfo::force_donate()]
+		fn force_donate(
+			origin,
+			from: T::AccountId,
+			amount: BalanceOf<T, I>,
+		) -> DispatchResult {
+			ensure_root(origin)?;
+
+			Self::do_donate(&from, amount, Default::default())?;
+			Ok(())
+		}
+
+		/// Fold `from`'s recorded contribution into `into`'s, for a donor who has moved accounts
+		///
+		/// Only the records change; no funds are moved. `from` is left with no contribution and
+		/// `into` takes its place on the leaderboard for the combined total. Requires root origin.
+		#[weight = T::WeightInfo::merge_donor_records()]
+		fn merge_donor_records(
+			origin,
+			from: T::AccountId,
+			into: T::AccountId,
+		) -> DispatchResult {
+			ensure_root(origin)?;
+			ensure!(from != into, Error::<T, I>::CannotMergeIntoSelf);
+
+			let moved = <Donations<T, I>>::take(&from);
+			if !moved.is_zero() {
+				let existing = Self::donation_of(&into);
+				if !existing.is_zero() {
+					// Two donors have become one
+					<DistinctDonors<I>>::mutate(|count| *count = count.saturating_sub(1));
+				}
+				let merged = existing.saturating_add(moved);
+				<Donations<T, I>>::insert(&into, merged);
+				Self::update_top_donors(&from, Zero::zero());
+				Self::update_top_donors(&into, merged);
+			}
+
+			Self::deposit_event(RawEvent::DonorRecordsMerged(from, into, moved));
+			Ok(())
+		}
+
+		/// Cancel the caller's recurring donation, returning its deposit
+		#[weight = T::WeightInfo::cancel_recurring_donation()]
+		fn cancel_recurring_donation(origin) -> DispatchResult {
+			let donor = ensure_signed(origin)?;
+			ensure!(
+				<RecurringDonations<T, I>>::contains_key(&donor),
+				Error::<T, I>::NoRecurringDonation
+			);
+
+			<RecurringDonations<T, I>>::remove(&donor);
+			T::Currency::unreserve(&donor, <RecurringDonationDeposits<T, I>>::take(&donor));
+			<RecurringDonationCount<I>>::mutate(|count| *count = count.saturating_sub(1));
+
+			Self::deposit_event(RawEvent::RecurringDonationCancelled(donor));
+			Ok(())
+		}
+
+		/// Set or clear the most a single allocation may move
+		///
+		/// This limits the damage a compromised governance origin can do in one call. It applies to
+		/// every allocation governance makes, including the whole of a vesting grant, and to each
+		/// payment of a batch or proportional split on its own; `None` removes the limit. Requires
+		/// root origin.
+		#[weight = T::WeightInfo::set_allocation_limit()]
+		fn set_allocation_limit(origin, limit: Option<BalanceOf<T, I>>) -> DispatchResult {
+			ensure_root(origin)?;
+			<AllocationLimit<T, I>>::set(limit);
+			Self::deposit_event(RawEvent::AllocationLimitSet(limit));
+			Ok(())
+		}
+
+		/// Restrict donations to accounts on the allowlist, or open them up to everyone again
+		///
+		/// The allowlist itself is kept either way. Requires root origin.
+		#[weight = T::WeightInfo::set_allowlist_enabled()]
+		fn set_allowlist_enabled(origin, enabled: bool) -> DispatchResult {
+			ensure_root(origin)?;
+			<AllowlistEnabled<I>>::put(enabled);
+			Self::deposit_event(RawEvent::AllowlistEnabledSet(enabled));
+			Ok(())
+		}
+
+		/// Allow an account to donate while the allowlist is enabled. Requires root origin.
+		#[weight = T::WeightInfo::add_to_allowlist()]
+		fn add_to_allowlist(origin, who: T::AccountId) -> DispatchResult {
+			ensure_root(origin)?;
+			<Allowlist<T, I>>::insert(&who, true);
+			Self::deposit_event(RawEvent::AddedToAllowlist(who));
+			Ok(())
+		}
+
+		/// Take an account off the allowlist. Requires root origin.
+		#[weight = T::WeightInfo::remove_from_allowlist()]
+		fn remove_from_allowlist(origin, who: T::AccountId) -> DispatchResult {
+			ensure_root(origin)?;
+			<Allowlist<T, I>>::remove(&who);
+			Self::deposit_event(RawEvent::RemovedFromAllowlist(who));
+			Ok(())
+		}
+
+		/// Keep the caller off the leaderboard
+		///
+		/// Their donations still count towards the pot and their own record, but they leave
+		/// `TopDonors` straight away and do not appear on it again until `show_on_leaderboard`.
+		#[weight = T::WeightInfo::hide_from_leaderboard()]
+		fn hide_from_leaderboard(origin) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			<AnonymousDonors<T, I>>::insert(&who, true);
+			<TopDonors<T, I>>::mutate(|board| board.retain(|(donor, _)| *donor != who));
+			Self::deposit_event(RawEvent::AnonymitySet(who, true));
+			Ok(())
+		}
+
+		/// Let the caller appear on the leaderboard again, placed by their recorded contribution
+		#[weight = T::WeightInfo::show_on_leaderboard()]
+		fn show_on_leaderboard(origin) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			<AnonymousDonors<T, I>>::remove(&who);
+			Self::update_top_donors(&who, Self::donation_of(&who));
+			Self::deposit_event(RawEvent::AnonymitySet(who, false));
+			Ok(())
+		}
+
+		/// Pause the charity
+		///
+		/// A circuit breaker for operators: while paused, `donate` and the allocation calls fail
+		/// with `PalletPaused`. Donors may still withdraw their unspent contributions.
+		#[weight = T::WeightInfo::pause()]
+		fn pause(origin) -> DispatchResult {
+			ensure_root(origin)?;
+			<Paused<I>>::put(true);
+			Self::deposit_event(RawEvent::Paused);
+			Ok(())
+		}
+
+		/// Unpause the charity so donations and allocations resume
+		#[weight = T::WeightInfo::unpause()]
+		fn unpause(origin) -> DispatchResult {
+			ensure_root(origin)?;
+			<Paused<I>>::put(false);
+			Self::deposit_event(RawEvent::Unpaused);
+			Ok(())
+		}
+
+		/// Move the whole pot to a safe account during an incident
+		///
+		/// Only works while the charity is paused, so it can't stand in for a normal allocation.
+		/// Funds locked for vesting or queued allocations stay behind to honour them. The pot
+		/// account may be reaped. Requires root origin.
+		#[weight = T::WeightInfo::sweep_to_account()]
+		fn sweep_to_account(origin, dest: T::AccountId) -> DispatchResult {
+			ensure_root(origin)?;
+			ensure!(Self::is_paused(), Error::<T, I>::NotPaused);
+
+			let amount = Self::pot();
+			Self::pay_from_pot(&dest, amount, AllowDeath)?;
+
+			Self::deposit_event(RawEvent::PotSwept(dest, amount));
+			Ok(())
+		}
+
+		/// Move the pot to the account derived from a new pallet ID
+		///
+		/// Everything above the old account's existential deposit is transferred, and the charity
+		/// uses the new account from then on. When `UseReserved` is set, the new account keeps its
+		/// own existential deposit free, as the pot does from genesis, and the rest is reserved.
+		/// Requires root origin.
+		#[weight = T::WeightInfo::migrate_pot()]
+		#[transactional]
+		fn migrate_pot(origin, new_id: [u8; 8]) -> DispatchResult {
+			ensure_root(origin)?;
+			let new_id = ModuleId(new_id);
+			ensure!(Self::current_pot_id() != new_id, Error::<T, I>::SamePotId);
+			ensure!(Self::locked_funds().is_zero(), Error::<T, I>::FundsLocked);
+
+			let old = Self::account_id();
+			let new: T::AccountId = new_id.into_account();
+			let amount = Self::spendable_pot();
+			Self::pay_from_pot(&new, amount, KeepAlive)?;
+			<PotId<I>>::put(new_id);
+			if T::UseReserved::get() {
+				// Reserving everything would leave the free balance below the existential
+				// deposit, and every payout that keeps the pot alive would then fail
+				let deposit = T::Currency::minimum_balance();
+				T::Currency::reserve(&new, amount.saturating_sub(deposit))?;
+			}
+
+			Self::deposit_event(RawEvent::PotMigrated(old, new, amount));
+			Ok(())
+		}
+	}
+}
+
+impl<T: Config<I>, I: Instance> Module<T, I> {
+	/// The account ID that holds the Charity's funds
+	pub fn account_id() -> T::AccountId {
+		Self::current_pot_id().into_account()
+	}
+
+	/// The ID the pot account is currently derived from
+	fn current_pot_id() -> ModuleId {
+		Self::pot_id().unwrap_or_else(T::ModuleId::get)
+	}
+
+	/// The account ID of one of the charity's numbered sub-accounts
+	///
+	/// Runtimes can use these to keep funds in separate buckets alongside the main pot. The index
+	/// is tagged before deriving the account; a bare `0` would encode to the same bytes as the
+	/// pot account's own padding and give back the pot itself.
+	pub fn sub_account_id(index: u8) -> T::AccountId {
+		T::ModuleId::get().into_sub_account((*b"sub", index))
+	}
+
+	/// The free balance of one of the charity's numbered sub-accounts
+	pub fn sub_pot(index: u8) -> BalanceOf<T, I> {
+		T::Currency::free_balance(&Self::sub_account_id(index))
+	}
+
+	/// The account ID that holds the matching pool
+	/// Derived from the configured `ModuleId` so each instance keeps its own pool
+	pub fn matching_account_id() -> T::AccountId {
+		T::ModuleId::get().into_sub_account(*b"matching")
+	}
+
+	/// The Charity's balance
+	///
+	/// This is what the `CharityApi` runtime API reports, so off-chain clients don't need to derive
+	/// the pot account themselves. When `UseReserved` is set, this is the pot account's reserved
+	/// balance; otherwise it is the free balance. Either way, funds locked for proposals are left
+	/// out.
+	pub fn pot() -> BalanceOf<T, I> {
+		if T::UseReserved::get() {
+			T::Currency::reserved_balance(&Self::account_id()).saturating_sub(Self::locked_funds())
+		} else {
+			T::Currency::free_balance(&Self::account_id())
+		}
+	}
+
+	/// How much `allocate` may pay out right now
+	///
+	/// Vesting schedules and queued allocations lock their full amount when they are created, so
+	/// the funds they still owe are already left out of `pot`; what remains is free to allocate.
+	pub fn available_to_allocate() -> BalanceOf<T, I> {
+		Self::pot()
+	}
+
+	/// Whether `allocate` would pay out `amount` right now without the pot dipping below its
+	/// existential deposit
+	///
+	/// Tooling can check this, through the `CharityApi` runtime API, before proposing an
+	/// allocation that would otherwise fail.
+	pub fn can_allocate(amount: BalanceOf<T, I>) -> bool {
+		Self::spendable_pot() >= amount && Self::ensure_allocatable(amount).is_ok()
+	}
+
+	/// Allocate `amount` to `dest` if the pot can cover it, returning whether it did
+	///
+	/// This is `allocate` for other pallets and scripts that try several candidates in turn: it
+	/// applies the same checks, but a failed allocation leaves the pot untouched instead of
+	/// returning an error. The caller is trusted to have the right to allocate.
+	pub fn allocate_if_sufficient(dest: &T::AccountId, amount: BalanceOf<T, I>) -> bool {
+		if Self::is_paused() || *dest == Self::account_id() {
+			return false;
+		}
+		let paid = match Self::try_allocate(dest, amount) {
+			Ok(paid) => paid,
+			Err(_) => return false,
+		};
+
+		Self::deposit_event(RawEvent::FundsAllocated(dest.clone(), paid, Self::pot()));
+		true
+	}
+
+	/// The pot balance that allocating `amount` would leave behind, without allocating anything
+	///
+	/// Fails with the error `allocate` would return for `amount`, whatever the destination.
+	/// Governance UIs can call this through the `CharityApi` runtime API to preview a proposal.
+	pub fn simulate_allocation(amount: BalanceOf<T, I>) -> Result<BalanceOf<T, I>, DispatchError> {
+		Self::ensure_allocatable(amount)?;
+		Ok(Self::pot().saturating_sub(amount))
+	}
+
+	/// `who`'s recorded contribution as a share of every donor's recorded contributions
+	///
+	/// This is the share of the spendable pot a donor would get back if the charity were
+	/// dissolved through `refund_all`, before rounding. Funds with no donor on record, such as a
+	/// genesis endowment, are shared out rather than counted as anyone's contribution. Zero when
+	/// no donor is on record. This reads every donor's record.
+	pub fn pot_share_of(who: &T::AccountId) -> Perbill {
+		let total = Self::recorded_donations();
+		if total.is_zero() {
+			return Perbill::zero();
+		}
+		Perbill::from_rational_approximation(Self::donation_of(who), total)
+	}
+
+	/// The sum of every donor's recorded contribution, which `refund_all` shares the pot out by
+	///
+	/// This can be less than `TotalDonated`, which also counts funds credited to no donor.
+	fn recorded_donations() -> BalanceOf<T, I> {
+		<Donations<T, I>>::iter_values().fold(Zero::zero(), |sum: BalanceOf<T, I>, given| {
+			sum.saturating_add(given)
+		})
+	}
+
+	/// What the charity still owes `dest` under vesting grants
+	///
+	/// A recipient has at most one schedule, so this is its unreleased remainder, or zero once
+	/// the grant has fully vested.
+	pub fn pending_vesting(dest: &T::AccountId) -> BalanceOf<T, I> {
+		Self::vesting_schedule_of(dest)
+			.map(|schedule| schedule.remaining)
+			.unwrap_or_else(Zero::zero)
+	}
+
+	/// The pot's headline figures in one snapshot
+	///
+	/// Each field is exactly what its own accessor reports; this just saves dashboards a round
+	/// trip per figure.
+	pub fn stats() -> CharityStats<BalanceOf<T, I>> {
+		CharityStats {
+			pot: Self::pot(),
+			total_donated: Self::total_donated(),
+			total_allocated: Self::total_allocated(),
+			distinct_donors: Self::distinct_donors(),
+			donation_count: Self::donation_count(),
+		}
+	}
+
+	/// Every registered cause alongside the funds currently set aside for it
+	///
+	/// This is what the `CharityApi` runtime API reports, so frontends can list causes without
+	/// iterating the `Causes` map.
+	pub fn causes() -> Vec<(Vec<u8>, BalanceOf<T, I>)> {
+		Self::cause_list()
+			.into_iter()
+			.map(|cause| {
+				let tally = Self::cause_balance(&cause);
+				(cause.into_inner(), tally)
+			})
+			.collect()
+	}
+
+	/// The 1-based place of `who` on the `TopDonors` leaderboard, or `None` if they aren't on it
+	pub fn donation_rank(who: &T::AccountId) -> Option<u32> {
+		Self::top_donors()
+			.iter()
+			.position(|(donor, _)| donor == who)
+			.map(|place| place as u32 + 1)
+	}
+
+	/// Hold back part of the pot while a proposal to spend it is pending
+	///
+	/// Locked funds cannot be allocated or withdrawn until `release_lock` returns them. They leave
+	/// the pot here rather than when they are paid, so this is where `MinPotReserve` applies.
+	fn lock_for_proposal(amount: BalanceOf<T, I>) -> DispatchResult {
+		ensure!(
+			amount <= Self::spendable_pot(),
+			Error::<T, I>::InsufficientPot
+		);
+		Self::ensure_keeps_reserve(amount)?;
+		// In reserved mode the funds are already reserved; the tally alone keeps them out of the pot
+		if !T::UseReserved::get() {
+			T::Currency::reserve(&Self::account_id(), amount)?;
+		}
+		<LockedFunds<T, I>>::mutate(|locked| *locked = locked.saturating_add(amount));
+		Ok(())
+	}
+
+	/// Return funds locked by `lock_for_proposal` to the pot
+	fn release_lock(amount: BalanceOf<T, I>) -> DispatchResult {
+		let locked = Self::locked_funds();
+		ensure!(amount <= locked, Error::<T, I>::InsufficientLocked);
+		if !T::UseReserved::get() {
+			T::Currency::unreserve(&Self::account_id(), amount);
+		}
+		<LockedFunds<T, I>>::put(locked.saturating_sub(amount));
+		Ok(())
+	}
+
+	/// The part of the pot that can be paid out without reaping the pot account
+	///
+	/// Reserved funds sit on top of the free existential deposit, so all of them can be spent.
+	fn spendable_pot() -> BalanceOf<T, I> {
+		if T::UseReserved::get() {
+			Self::pot()
+		} else {
+			Self::pot().saturating_sub(T::Currency::minimum_balance())
+		}
+	}
+
+	/// Count `donor` in `DistinctDonors` if crediting them `amount` gives them their first
+	/// contribution on record
+	///
+	/// Must be called before `Donations` is updated.
+	fn note_first_donation(donor: &T::AccountId, amount: BalanceOf<T, I>) {
+		if !amount.is_zero() && Self::donation_of(donor).is_zero() {
+			<DistinctDonors<I>>::mutate(|count| *count = count.saturating_add(1));
+		}
+	}
+
+	/// Move funds from `source` into the pot, reserving them if `UseReserved` is set
+	fn deposit_into_pot(source: &T::AccountId, amount: BalanceOf<T, I>) -> DispatchResult {
+		let pot = Self::account_id();
+		T::Currency::transfer(source, &pot, amount, AllowDeath)?;
+		if T::UseReserved::get() {
+			T::Currency::reserve(&pot, amount)?;
+		}
+		Ok(())
+	}
+
+	/// Pay an allocation out of the pot and count it towards `TotalAllocated`
+	fn pay_allocation(
+		dest: &T::AccountId,
+		amount: BalanceOf<T, I>,
+		existence: ExistenceRequirement,
+	) -> DispatchResult {
+		Self::pay_from_pot(dest, amount, existence)?;
+		<TotalAllocated<T, I>>::mutate(|total| *total = total.saturating_add(amount));
+		Ok(())
+	}
+
+	/// Pay an allocation governance has made, counting it towards `MaxSpendPerBlock`
+	///
+	/// Otherwise this is `pay_allocation_with_fee`. Returns what `dest` received.
+	#[transactional]
+	fn pay_governed_allocation(
+		dest: &T::AccountId,
+		amount: BalanceOf<T, I>,
+		existence: ExistenceRequirement,
+	) -> Result<BalanceOf<T, I>, DispatchError> {
+		Self::note_block_spend(amount)?;
+		Self::pay_allocation_with_fee(dest, amount, existence)
+	}
+
+	/// Allocate `amount` to `dest`, less the `AllocationFee` which goes to `OperationsAccount`
+	///
+	/// Every allocation is paid through here, so the fee applies to all of them alike, and
+	/// `OnAllocation` hears about each one. Either both payments are made or neither is. Returns
+	/// what `dest` received.
+	#[transactional]
+	fn pay_allocation_with_fee(
+		dest: &T::AccountId,
+		amount: BalanceOf<T, I>,
+		existence: ExistenceRequirement,
+	) -> Result<BalanceOf<T, I>, DispatchError> {
+		let fee = T::AllocationFee::get() * amount;
+		if !fee.is_zero() {
+			Self::pay_allocation(&T::OperationsAccount::get(), fee, existence)?;
+			Self::deposit_event(RawEvent::AllocationFeeCharged(dest.clone(), fee));
+		}
+		let paid = amount.saturating_sub(fee);
+		Self::pay_allocation(dest, paid, existence)?;
+		T::OnAllocation::on_allocation(dest, paid);
+		Ok(paid)
+	}
+
+	/// Pay funds out of the pot, unreserving them first if `UseReserved` is set
+	fn pay_from_pot(
+		dest: &T::AccountId,
+		amount: BalanceOf<T, I>,
+		existence: ExistenceRequirement,
+	) -> DispatchResult {
+		let pot = Self::account_id();
+		if !T::UseReserved::get() {
+			return T::Currency::transfer(&pot, dest, amount, existence);
+		}
+
+		ensure!(
+			T::Currency::unreserve(&pot, amount).is_zero(),
+			Error::<T, I>::InsufficientPot
+		);
+		let result = T::Currency::transfer(&pot, dest, amount, existence);
+		if result.is_err() {
+			// Put the funds back where they were so a failed payment leaves no trace
+			let _ = T::Currency::reserve(&pot, amount);
+		}
+		result
+	}
+
+	/// Move a donation into the pot and record it
+	///
+	/// This is shared by every path through which a donor gives to the charity, so checks that
+	/// apply to all donations belong here. If the donation would take the pot above
+	/// `MaxPotBalance`, only enough to fill the pot is donated and recorded; the rest goes to the
+	/// overflow destination. Whatever reaches the pot is matched from the matching pool, as far as
+	/// the pool and the cap allow.
+	///
+	/// Returns the part of `amount` that reached the pot.
+	fn do_donate(
+		donor: &T::AccountId,
+		amount: BalanceOf<T, I>,
+		memo: MemoOf<T, I>,
+	) -> Result<BalanceOf<T, I>, DispatchError> {
+		Self::do_donate_for(donor, donor, amount, memo)
+	}
+
+	/// Like `do_donate`, but the funds come from `payer` while the donation is credited to `donor`
+	#[transactional]
+	fn do_donate_for(
+		payer: &T::AccountId,
+		donor: &T::AccountId,
+		amount: BalanceOf<T, I>,
+		memo: MemoOf<T, I>,
+	) -> Result<BalanceOf<T, I>, DispatchError> {
+		Self::ensure_not_paused()?;
+		T::DonationValidator::validate(donor, amount)?;
+		Self::ensure_allowed(payer)?;
+		Self::ensure_allowed(donor)?;
+		ensure!(
+			amount >= T::MinimumDonation::get(),
+			Error::<T, I>::DonationTooSmall
+		);
+		Self::ensure_whole_units(amount)?;
+		Self::ensure_cooled_down(donor)?;
+		Self::note_period_donation(donor, amount)?;
+
+		let gross = amount;
+		let fee = T::DonationFee::get() * gross;
+		if !fee.is_zero() {
+			T::Currency::transfer(payer, &T::FeeCollector::get(), fee, AllowDeath)
+				.map_err(|_| Error::<T, I>::InsufficientFunds)?;
+			Self::deposit_event(RawEvent::DonationFeeCharged(donor.clone(), fee));
+		}
+		let amount = gross.saturating_sub(fee);
+
+		let room = T::MaxPotBalance::get().saturating_sub(Self::pot());
+		let to_pot = amount.min(room);
+		let overflow = amount.saturating_sub(to_pot);
+
+		// Lifetime tallies are not reduced by allocations, so they can outgrow the total issuance
+		let total = Self::total_donated()
+			.checked_add(&to_pot)
+			.ok_or(Error::<T, I>::ArithmeticOverflow)?;
+		let donor_total = Self::donation_of(donor)
+			.checked_add(&to_pot)
+			.ok_or(Error::<T, I>::ArithmeticOverflow)?;
+
+		Self::deposit_into_pot(payer, to_pot).map_err(|_| Error::<T, I>::InsufficientFunds)?;
+		<TotalDonated<T, I>>::put(total);
+		Self::note_first_donation(donor, to_pot);
+		// Only contributions count towards `DistinctDonors`, so nothing is recorded without one
+		if !donor_total.is_zero() {
+			<Donations<T, I>>::insert(donor, donor_total);
+		}
+		let old_rank = Self::donation_rank(donor);
+		Self::update_top_donors(donor, donor_total);
+		let new_rank = Self::donation_rank(donor);
+		let count = <DonationCount<I>>::mutate(|count| {
+			*count = count.saturating_add(1);
+			*count
+		});
+		let receipt_id = <NextReceiptId<I>>::mutate(|next| {
+			let id = *next;
+			*next = next.wrapping_add(1);
+			id
+		});
+		<Receipts<T, I>>::insert(
+			receipt_id,
+			DonationReceipt {
+				donor: donor.clone(),
+				amount: to_pot,
+				block: <frame_system::Module<T>>::block_number(),
+			},
+		);
+
+		Self::deposit_event(RawEvent::DonationReceived(
+			donor.clone(),
+			gross,
+			to_pot,
+			Self::pot(),
+			memo,
+			count,
+			donor_total,
+			receipt_id,
+		));
+
+		if to_pot > Self::largest_donation().1 {
+			<LargestDonation<T, I>>::put((donor.clone(), to_pot));
+			Self::deposit_event(RawEvent::NewRecordDonation(donor.clone(), to_pot));
+		}
+		if new_rank != old_rank {
+			Self::deposit_event(RawEvent::RankChanged(donor.clone(), old_rank, new_rank));
+		}
+		Self::update_streak(donor);
+
+		let matched = to_pot
+			.min(Self::matching_pool())
+			.min(T::MaxPotBalance::get().saturating_sub(Self::pot()));
+		if !matched.is_zero() {
+			Self::deposit_into_pot(&Self::matching_account_id(), matched)?;
+			<MatchingPool<T, I>>::mutate(|pool| *pool = pool.saturating_sub(matched));
+			Self::deposit_event(RawEvent::DonationMatched(donor.clone(), matched));
+		}
+
+		if !overflow.is_zero() {
+			T::Currency::transfer(payer, &T::OverflowDestination::get(), overflow, AllowDeath)
+				.map_err(|_| Error::<T, I>::InsufficientFunds)?;
+			Self::deposit_event(RawEvent::DonationOverflowed(donor.clone(), overflow));
+		}
+
+		let pot = Self::pot();
+		let mut milestones = T::Milestones::get();
+		milestones.sort();
+		for milestone in milestones {
+			if pot >= milestone && !Self::milestone_crossed(milestone) {
+				<CrossedMilestones<T, I>>::insert(milestone, true);
+				Self::deposit_event(RawEvent::MilestoneReached(milestone));
+			}
+		}
+
+		T::OnDonation::on_donation(donor, to_pot);
+		Ok(to_pot)
+	}
+
+	/// Unlock funds held by `lock_for_proposal` and pay them out of the pot
+	#[transactional]
+	fn pay_locked(dest: &T::AccountId, amount: BalanceOf<T, I>) -> DispatchResult {
+		Self::release_lock(amount)?;
+		Self::pay_governed_allocation(dest, amount, AllowDeath)?;
+		Ok(())
+	}
+
+	/// Check `amount` against every rule `allocate` applies to it, without paying anything
+	///
+	/// The charity must not be paused, and `amount` must be within the allocation limit, the
+	/// available pot, `MinPotReserve` and what is left of this block's `MaxSpendPerBlock`.
+	fn ensure_allocatable(amount: BalanceOf<T, I>) -> DispatchResult {
+		Self::ensure_not_paused()?;
+		Self::ensure_within_allocation_limit(amount)?;
+		ensure!(
+			Self::available_to_allocate() >= amount,
+			Error::<T, I>::InsufficientPot
+		);
+		Self::ensure_keeps_reserve(amount)?;
+		ensure!(
+			Self::spent_this_block().saturating_add(amount) <= T::MaxSpendPerBlock::get(),
+			Error::<T, I>::BlockSpendLimitReached
+		);
+		Ok(())
+	}
+
+	/// Fail with `PalletPaused` if the charity is currently paused
+	fn ensure_not_paused() -> DispatchResult {
+		ensure!(!Self::is_paused(), Error::<T, I>::PalletPaused);
+		Ok(())
+	}
+
+	/// Pay one allocation from the pot if it is within the limit and the pot can cover it,
+	/// returning what `dest` received once the `AllocationFee` was taken
+	///
+	/// Fails with `CannotAllocateToSelf` if `dest` is the pot account.
+	#[transactional]
+	fn try_allocate(
+		dest: &T::AccountId,
+		amount: BalanceOf<T, I>,
+	) -> Result<BalanceOf<T, I>, DispatchError> {
+		ensure!(
+			*dest != Self::account_id(),
+			Error::<T, I>::CannotAllocateToSelf
+		);
+		Self::ensure_allocatable(amount)?;
+		Self::pay_governed_allocation(dest, amount, T::AllocationExistenceRequirement::get())
+	}
+
+	/// Check that `name` fits within `MaxCauseNameLength`
+	fn cause_name(name: Vec<u8>) -> Result<CauseNameOf<T, I>, DispatchError> {
+		name.try_into()
+			.map_err(|_| Error::<T, I>::CauseNameTooLong.into())
+	}
+
+	/// Count `amount` towards `cause`, registering the cause in `CauseList` if it is new
+	fn credit_cause(cause: &CauseNameOf<T, I>, amount: BalanceOf<T, I>) -> DispatchResult {
+		if !<Causes<T, I>>::contains_key(cause) {
+			<CauseList<T, I>>::try_mutate(|list| -> DispatchResult {
+				if !list.contains(cause) {
+					ensure!(
+						list.len() < T::MaxCauses::get() as usize,
+						Error::<T, I>::TooManyCauses
+					);
+					list.push(cause.clone());
+				}
+				Ok(())
+			})?;
+		}
+		<Causes<T, I>>::mutate(cause, |tally| *tally = tally.saturating_add(amount));
+		Ok(())
+	}
+
+	/// Emit `PotRunningLow` when the pot drops below `LowPotThreshold`
+	///
+	/// The warning is given once per crossing; `WasLow` is cleared again once the pot recovers.
+	fn check_pot_level() {
+		let threshold = T::LowPotThreshold::get();
+		if threshold.is_zero() {
+			return;
+		}
+
+		let pot = Self::pot();
+		let was_low = Self::was_low();
+		if pot < threshold && !was_low {
+			<WasLow<I>>::put(true);
+			Self::deposit_event(RawEvent::PotRunningLow(pot));
+		} else if pot >= threshold && was_low {
+			<WasLow<I>>::kill();
+		}
+	}
+
+	/// Fail with `DonationTooSoon` if `who` last donated fewer than `DonationCooldown` blocks ago
+	fn ensure_cooled_down(who: &T::AccountId) -> DispatchResult {
+		let cooldown = T::DonationCooldown::get();
+		if !cooldown.is_zero() && <LastDonationBlock<T, I>>::contains_key(who) {
+			let now = <frame_system::Module<T>>::block_number();
+			ensure!(
+				now.saturating_sub(Self::last_donation_block(who)) >= cooldown,
+				Error::<T, I>::DonationTooSoon
+			);
+		}
+		Ok(())
+	}
+
+	/// Count `amount` towards what `who` has given this `DonationPeriod`, failing with
+	/// `PeriodCapExceeded` if that would take them past `PerDonorPeriodCap`
+	///
+	/// A donor's tally starts again from zero in each new period.
+	fn note_period_donation(who: &T::AccountId, amount: BalanceOf<T, I>) -> DispatchResult {
+		let period = T::DonationPeriod::get();
+		if period.is_zero() {
+			return Ok(());
+		}
+		let current = <frame_system::Module<T>>::block_number() / period;
+		let (last, given) = Self::donated_in_period(who);
+		let given = if last == current { given } else { Zero::zero() };
+		let given = given.saturating_add(amount);
+		ensure!(
+			given <= T::PerDonorPeriodCap::get(),
+			Error::<T, I>::PeriodCapExceeded
+		);
+		<DonatedInPeriod<T, I>>::insert(who, (current, given));
+		Ok(())
+	}
+
+	/// Extend `who`'s donation streak if they last donated within `StreakWindow` blocks, or start a
+	/// new one
+	///
+	/// Further donations in the same block leave the streak as it is.
+	fn update_streak(who: &T::AccountId) {
+		let now = <frame_system::Module<T>>::block_number();
+		let last = <LastDonationBlock<T, I>>::get(who);
+		let streak = Self::donation_streak(who);
+		if streak > 0 && last == now {
+			return;
+		}
+
+		let streak = if streak > 0 && now.saturating_sub(last) <= T::StreakWindow::get() {
+			streak.saturating_add(1)
+		} else {
+			1
+		};
+		<LastDonationBlock<T, I>>::insert(who, now);
+		<DonationStreak<T, I>>::insert(who, streak);
+		if T::StreakThresholds::get().contains(&streak) {
+			Self::deposit_event(RawEvent::StreakExtended(who.clone(), streak));
+		}
+	}
+
+	/// Move `who` to its place on the leaderboard for a recorded contribution of `total`
+	///
+	/// A donor whose total drops to zero leaves the board, and anonymous donors never join it.
+	/// Donors displaced when it was full only return by donating again, even if someone above them
+	/// later withdraws.
+	fn update_top_donors(who: &T::AccountId, total: BalanceOf<T, I>) {
+		let anonymous = Self::is_anonymous(who);
+		<TopDonors<T, I>>::mutate(|board| {
+			board.retain(|(donor, _)| donor != who);
+			if total.is_zero() || anonymous {
+				return;
+			}
+			// Ties go to whoever got there first
+			let place = board
+				.iter()
+				.position(|(_, given)| *given < total)
+				.unwrap_or(board.len());
+			board.insert(place, (who.clone(), total));
+			board.truncate(T::MaxLeaderboard::get() as usize);
+		});
+	}
+
+	/// Whether the pot balance should be recorded at the end of block `now`
+	fn snapshot_due(now: T::BlockNumber) -> bool {
+		let interval = T::SnapshotInterval::get();
+		!interval.is_zero() && (now % interval).is_zero()
+	}
+
+	/// Fail with `DonorNotAllowed` if the allowlist is enabled and `who` is not on it
+	fn ensure_allowed(who: &T::AccountId) -> DispatchResult {
+		ensure!(
+			!Self::allowlist_enabled() || Self::is_allowed(who),
+			Error::<T, I>::DonorNotAllowed
+		);
+		Ok(())
+	}
+
+	/// Fail with `NotUnitMultiple` unless `amount` is a whole multiple of `DonationUnit`
+	///
+	/// Odd amounts are turned away rather than rounded down, so no change ever has to be refunded.
+	fn ensure_whole_units(amount: BalanceOf<T, I>) -> DispatchResult {
+		let unit = T::DonationUnit::get();
+		ensure!(
+			unit.is_zero() || (amount % unit).is_zero(),
+			Error::<T, I>::NotUnitMultiple
+		);
+		Ok(())
+	}
+
+	/// Fail with `AllocationExceedsLimit` if `amount` is more than one allocation may move
+	fn ensure_within_allocation_limit(amount: BalanceOf<T, I>) -> DispatchResult {
+		if let Some(limit) = Self::allocation_limit() {
+			ensure!(amount <= limit, Error::<T, I>::AllocationExceedsLimit);
+		}
+		Ok(())
+	}
+
+	/// Fill in `TotalDonated` for storage that predates it
+	///
+	/// The total is the sum of every donor's credit. Where no donor has been recorded, the pot
+	/// itself is the best estimate of what has been given.
+	fn migrate_to_v2() -> Weight {
+		let mut reads: Weight = 1;
+		let mut total = BalanceOf::<T, I>::zero();
+		for (_, amount) in <Donations<T, I>>::iter() {
+			reads = reads.saturating_add(1);
+			total = total.saturating_add(amount);
+		}
+		if reads == 1 {
+			reads = reads.saturating_add(1);
+			total = Self::pot();
+		}
+
+		<TotalDonated<T, I>>::put(total);
+		<CurrentStorageVersion<I>>::put(StorageVersion::V2);
+		Self::deposit_event(RawEvent::MigrationCompleted(StorageVersion::V2));
+		T::DbWeight::get().reads_writes(reads, 2)
+	}
+
+	/// Check the pallet's invariants before `on_runtime_upgrade` runs
+	///
+	/// FRAME 3.0's `OnRuntimeUpgrade` has no try-runtime hooks of its own, so a try-runtime
+	/// harness calls this and `post_upgrade` around the upgrade itself.
+	#[cfg(feature = "try-runtime")]
+	pub fn pre_upgrade() -> Result<(), &'static str> {
+		Self::check_invariants()
+	}
+
+	/// Check that the upgrade left the storage at the current version with its invariants intact
+	#[cfg(feature = "try-runtime")]
+	pub fn post_upgrade() -> Result<(), &'static str> {
+		if Self::storage_version() != StorageVersion::V2 {
+			return Err("charity storage was not migrated to V2");
+		}
+		Self::check_invariants()
+	}
+
+	/// The invariants `pre_upgrade` and `post_upgrade` hold the storage to
+	///
+	/// The pot is not compared against `TotalDonated` and `TotalAllocated`: absorbed imbalances
+	/// also fund it and are not tallied, so only the bounds are checked.
+	#[cfg(feature = "try-runtime")]
+	fn check_invariants() -> Result<(), &'static str> {
+		let credited = <Donations<T, I>>::iter()
+			.fold(BalanceOf::<T, I>::zero(), |sum, (_, amount)| {
+				sum.saturating_add(amount)
+			});
+		if credited > Self::total_donated() {
+			return Err("donor credits exceed TotalDonated");
+		}
+		if T::Currency::total_balance(&Self::account_id()) < Self::locked_funds() {
+			return Err("pot holds less than its locked funds");
+		}
+		Ok(())
+	}
+
+	/// Count `amount` towards this block's spending, failing with `BlockSpendLimitReached` if that
+	/// would take it past `MaxSpendPerBlock`
+	fn note_block_spend(amount: BalanceOf<T, I>) -> DispatchResult {
+		let spent = Self::spent_this_block().saturating_add(amount);
+		ensure!(
+			spent <= T::MaxSpendPerBlock::get(),
+			Error::<T, I>::BlockSpendLimitReached
+		);
+		<SpentThisBlock<T, I>>::put(spent);
+		Ok(())
+	}
+
+	/// Fail with `ExceedsBlockSpendLimit` if `amount` could never fit in one block's allowance
+	///
+	/// Queued payments are made in `on_initialize`, which would otherwise retry one that is too
+	/// large in every block while its funds stayed locked.
+	fn ensure_payable_in_one_block(amount: BalanceOf<T, I>) -> DispatchResult {
+		ensure!(
+			amount <= T::MaxSpendPerBlock::get(),
+			Error::<T, I>::ExceedsBlockSpendLimit
+		);
+		Ok(())
+	}
+
+	/// Fail with `WouldBreachReserve` if paying out `amount` would take the pot below
+	/// `MinPotReserve`
+	fn ensure_keeps_reserve(amount: BalanceOf<T, I>) -> DispatchResult {
+		ensure!(
+			Self::available_to_allocate().saturating_sub(amount) >= T::MinPotReserve::get(),
+			Error::<T, I>::WouldBreachReserve
+		);
+		Ok(())
+	}
+
+	/// Absorb an imbalance into the pot, recording where it came from
+	///
+	/// Runtimes that know the source of an imbalance can route it here rather than through
+	/// `OnUnbalanced`, so that `ImbalanceAbsorbed` carries the source.
+	pub fn absorb_imbalance(amount: NegativeImbalanceOf<T, I>, source: ImbalanceKind) {
+		let absorbed = T::AbsorbFraction::get().deconstruct();
+		let (amount, burned) = amount.ration(absorbed, Perbill::one().deconstruct() - absorbed);
+		// Dropping the remainder burns it
+		drop(burned);
+		let numeric_amount = amount.peek();
+
+		// Must resolve into existing but better to be safe.
+		let _ = T::Currency::resolve_creating(&Self::account_id(), amount);
+		if T::UseReserved::get() {
+			let _ = T::Currency::reserve(&Self::account_id(), numeric_amount);
+		}
+
+		Self::deposit_event(RawEvent::ImbalanceAbsorbed(
+			numeric_amount,
+			Self::pot(),
+			source,
+		));
+	}
+}
+
+// This implementation allows the charity to be the recipient of funds that are burned elsewhere in
+// the runtime. For eample, it could be transaction fees, consensus-related slashing, or burns that
+// align incentives in other pallets.
+impl<T: Config<I>, I: Instance> OnUnbalanced<NegativeImbalanceOf<T, I>> for Module<T, I> {
+	fn on_nonzero_unbalanced(amount: NegativeImbalanceOf<T, I>) {
+		Self::absorb_imbalance(amount, ImbalanceKind::Unknown);
+	}
+}
+
+/// Routes another pallet's slashes into the pot, so they are recorded as `ImbalanceKind::Slash`
+///
+/// Drop it into a pallet's slash handler, as in `type Slash = charity::SlashToCharity<Runtime>;`.
+pub struct SlashToCharity<T, I = DefaultInstance>(sp_std::marker::PhantomData<(T, I)>);
+
+impl<T: Config<I>, I: Instance> OnUnbalanced<NegativeImbalanceOf<T, I>> for SlashToCharity<T, I> {
+	fn on_nonzero_unbalanced(amount: NegativeImbalanceOf<T, I>) {
+		<Module<T, I>>::absorb_imbalance(amount, ImbalanceKind::Slash);
+	}
+}
+
+/// Lets the charity pay for funds minted elsewhere in the runtime, such as a rebate, by withdrawing
+/// the same amount from the pot. If the pot can't cover it, the imbalance is dropped and the minted
+/// funds simply add to the total issuance.
+///
+/// This is a separate type rather than a second `OnUnbalanced` impl on `Module` because a currency's
+/// positive and negative imbalances are allowed to be the same type.
+pub struct SettleFromPot<T, I = DefaultInstance>(sp_std::marker::PhantomData<(T, I)>);
+
+impl<T: Config<I>, I: Instance> OnUnbalanced<PositiveImbalanceOf<T, I>> for SettleFromPot<T, I> {
+	fn on_nonzero_unbalanced(amount: PositiveImbalanceOf<T, I>) {
+		let numeric_amount = amount.peek();
+		// Never dip into funds locked for proposals or vesting
+		if <Module<T, I>>::spendable_pot() < numeric_amount {
+			return;
+		}
+
+		let pot_account = <Module<T, I>>::account_id();
+		let reserved = T::UseReserved::get();
+		if reserved {
+			T::Currency::unreserve(&pot_account, numeric_amount);
+		}
+		match T::Currency::settle(&pot_account, amount, WithdrawReasons::TRANSFER, KeepAlive) {
+			Ok(()) => {
+				<Module<T, I>>::deposit_event(RawEvent::PositiveImbalanceSettled(
+					numeric_amount,
+					<Module<T, I>>::pot(),
+				));
+			}
+			Err(_) => {
+				// Put the pot back the way it was
+				if reserved {
+					let _ = T::Currency::reserve(&pot_account, numeric_amount);
+				}
+			}
+		}
 	}
 }
@@ -0,0 +1,500 @@
+//! A mock runtime for the charity pallet's tests and benchmarks
+
+use crate::{
+	self as charity, AssetTransfer, Config, Instance1, OnAllocationHandler, OnDonationHandler,
+	ValidateDonation,
+};
+use frame_support::{
+	construct_runtime,
+	dispatch::DispatchResult,
+	ord_parameter_types, parameter_types,
+	traits::{
+		schedule::{Anon as ScheduleAnon, DispatchTime, Period, Priority},
+		Currency, ExistenceRequirement,
+	},
+};
+use frame_system::{EnsureOneOf, EnsureRoot, EnsureSignedBy};
+use sp_core::H256;
+use sp_runtime::{
+	testing::Header,
+	traits::{BlakeTwo256, Dispatchable, IdentityLookup},
+	BuildStorage, DispatchError, ModuleId, Perbill,
+};
+use std::collections::BTreeMap;
+
+type UncheckedExtrinsic = frame_system::mocking::MockUncheckedExtrinsic<TestRuntime>;
+pub type Block = frame_system::mocking::MockBlock<TestRuntime>;
+
+construct_runtime!(
+	pub enum TestRuntime where
+		Block = Block,
+		NodeBlock = Block,
+		UncheckedExtrinsic = UncheckedExtrinsic,
+	{
+		System: frame_system::{Module, Call, Config, Storage, Event<T>},
+		Balances: pallet_balances::{Module, Call, Storage, Config<T>, Event<T>},
+		Charity: charity::{Module, Call, Storage, Config<T>, Event<T>},
+		Endowment: charity::<Instance1>::{Module, Call, Storage, Config<T>, Event<T>},
+	}
+);
+
+parameter_types! {
+	pub const BlockHashCount: u64 = 250;
+	pub BlockWeights: frame_system::limits::BlockWeights =
+		frame_system::limits::BlockWeights::simple_max(1024);
+}
+impl frame_system::Config for TestRuntime {
+	type BaseCallFilter = ();
+	type BlockWeights = ();
+	type BlockLength = ();
+	type Origin = Origin;
+	type Index = u64;
+	type Call = Call;
+	type BlockNumber = u64;
+	type Hash = H256;
+	type Hashing = BlakeTwo256;
+	// u64 is too short to tell the charity's derived accounts apart
+	type AccountId = u128;
+	type Lookup = IdentityLookup<Self::AccountId>;
+	type Header = Header;
+	type Event = Event;
+	type BlockHashCount = BlockHashCount;
+	type DbWeight = ();
+	type Version = ();
+	type PalletInfo = PalletInfo;
+	type AccountData = pallet_balances::AccountData<u64>;
+	type OnNewAccount = ();
+	type OnKilledAccount = ();
+	type SystemWeightInfo = ();
+	type SS58Prefix = ();
+}
+
+parameter_types! {
+	pub const ExistentialDeposit: u64 = 1;
+}
+impl pallet_balances::Config for TestRuntime {
+	type MaxLocks = ();
+	type Balance = u64;
+	type Event = Event;
+	type DustRemoval = ();
+	type ExistentialDeposit = ExistentialDeposit;
+	type AccountStore = System;
+	type WeightInfo = ();
+}
+
+parameter_types! {
+	pub const MaxMemoLength: u32 = 16;
+	pub static MinimumDonation: u64 = 2;
+	pub static DonationUnit: u64 = 1;
+	pub static UseReserved: bool = false;
+	pub static AllocationExistenceRequirement: ExistenceRequirement = ExistenceRequirement::AllowDeath;
+	pub static MaxPotBalance: u64 = u64::MAX;
+	pub static Milestones: Vec<u64> = Vec::new();
+	pub static AbsorbFraction: Perbill = Perbill::one();
+	pub static StreakThresholds: Vec<u32> = Vec::new();
+	pub static CharityModuleId: ModuleId = ModuleId(*b"Charity!");
+	pub static DonationFee: Perbill = Perbill::zero();
+	pub static MinPotReserve: u64 = 0;
+	pub static DonationCooldown: u64 = 0;
+	pub static DonationPeriod: u64 = 0;
+	pub static PerDonorPeriodCap: u64 = u64::MAX;
+	pub static LowPotThreshold: u64 = 0;
+	pub static MaxSpendPerBlock: u64 = u64::MAX;
+	pub static AllocationFee: Perbill = Perbill::zero();
+	pub static RecurringDonationDeposit: u64 = 0;
+}
+
+parameter_types! {
+	pub const OverflowDestination: u128 = 99;
+	pub const FeeCollector: u128 = 98;
+	pub const OperationsAccount: u128 = 97;
+	pub const MaxCauseNameLength: u32 = 16;
+	pub const MaxBatchSize: u32 = 4;
+	pub const MaxCauses: u32 = 4;
+	pub const AllocationDelay: u64 = 3;
+	pub const SnapshotInterval: u64 = 5;
+	pub const MaxSnapshots: u32 = 3;
+	pub const MaxLeaderboard: u32 = 3;
+	pub const StreakWindow: u64 = 2;
+	pub const MaxRecurringDonations: u32 = 2;
+}
+
+parameter_types! {
+	pub const EndowmentModuleId: ModuleId = ModuleId(*b"Endowmnt");
+}
+
+ord_parameter_types! {
+	pub const Treasurer: u128 = 42;
+}
+impl Config for TestRuntime {
+	type Event = Event;
+	type Currency = Balances;
+	type ModuleId = CharityModuleId;
+	type UseReserved = UseReserved;
+	type AllocationExistenceRequirement = AllocationExistenceRequirement;
+	type MinimumDonation = MinimumDonation;
+	type DonationUnit = DonationUnit;
+	type MaxMemoLength = MaxMemoLength;
+	type MaxPotBalance = MaxPotBalance;
+	type OverflowDestination = OverflowDestination;
+	type MaxCauseNameLength = MaxCauseNameLength;
+	type MaxCauses = MaxCauses;
+	type MaxBatchSize = MaxBatchSize;
+	type OnDonation = RecordDonations;
+	type OnAllocation = RecordAllocations;
+	type DonationValidator = EvenDonationsOnly;
+	type Assets = MockAssets;
+	type AllocationDelay = AllocationDelay;
+	type Milestones = Milestones;
+	type AbsorbFraction = AbsorbFraction;
+	type SnapshotInterval = SnapshotInterval;
+	type MaxSnapshots = MaxSnapshots;
+	type MaxLeaderboard = MaxLeaderboard;
+	type StreakWindow = StreakWindow;
+	type StreakThresholds = StreakThresholds;
+	type DonationFee = DonationFee;
+	type FeeCollector = FeeCollector;
+	type MinPotReserve = MinPotReserve;
+	type DonationCooldown = DonationCooldown;
+	type DonationPeriod = DonationPeriod;
+	type PerDonorPeriodCap = PerDonorPeriodCap;
+	type LowPotThreshold = LowPotThreshold;
+	type MaxSpendPerBlock = MaxSpendPerBlock;
+	type AllocationFee = AllocationFee;
+	type OperationsAccount = OperationsAccount;
+	type MaxRecurringDonations = MaxRecurringDonations;
+	type RecurringDonationDeposit = RecurringDonationDeposit;
+	type ScheduledCall = Call;
+	type PalletsOrigin = OriginCaller;
+	type Scheduler = MockScheduler;
+	type AllocationOrigin = EnsureOneOf<u128, EnsureRoot<u128>, EnsureSignedBy<Treasurer, u128>>;
+	type WeightInfo = ();
+}
+
+// A second charity in the same runtime, with its own pot
+impl Config<Instance1> for TestRuntime {
+	type Event = Event;
+	type Currency = Balances;
+	type ModuleId = EndowmentModuleId;
+	type UseReserved = UseReserved;
+	type AllocationExistenceRequirement = AllocationExistenceRequirement;
+	type MinimumDonation = MinimumDonation;
+	type DonationUnit = DonationUnit;
+	type MaxMemoLength = MaxMemoLength;
+	type MaxPotBalance = MaxPotBalance;
+	type OverflowDestination = OverflowDestination;
+	type MaxCauseNameLength = MaxCauseNameLength;
+	type MaxCauses = MaxCauses;
+	type MaxBatchSize = MaxBatchSize;
+	type OnDonation = ();
+	type OnAllocation = ();
+	type DonationValidator = ();
+	type Assets = ();
+	type AllocationDelay = AllocationDelay;
+	type Milestones = Milestones;
+	type AbsorbFraction = AbsorbFraction;
+	type SnapshotInterval = SnapshotInterval;
+	type MaxSnapshots = MaxSnapshots;
+	type MaxLeaderboard = MaxLeaderboard;
+	type StreakWindow = StreakWindow;
+	type StreakThresholds = StreakThresholds;
+	type DonationFee = DonationFee;
+	type FeeCollector = FeeCollector;
+	type MinPotReserve = MinPotReserve;
+	type DonationCooldown = DonationCooldown;
+	type DonationPeriod = DonationPeriod;
+	type PerDonorPeriodCap = PerDonorPeriodCap;
+	type LowPotThreshold = LowPotThreshold;
+	type MaxSpendPerBlock = MaxSpendPerBlock;
+	type AllocationFee = AllocationFee;
+	type OperationsAccount = OperationsAccount;
+	type MaxRecurringDonations = MaxRecurringDonations;
+	type RecurringDonationDeposit = RecurringDonationDeposit;
+	type ScheduledCall = Call;
+	type PalletsOrigin = OriginCaller;
+	type Scheduler = MockScheduler;
+	type AllocationOrigin = EnsureRoot<u128>;
+	type WeightInfo = ();
+}
+
+// Implement the runtime side of the charity's API for the test runtime. In a real runtime this is
+// what the `impl_runtime_apis!` macro generates from the implementation in the runtime's lib.rs.
+impl charity_runtime_api::runtime_decl_for_CharityApi::CharityApi<Block, u128, u64>
+	for TestRuntime
+{
+	fn pot_balance() -> u64 {
+		Charity::pot()
+	}
+
+	fn pot_account() -> u128 {
+		Charity::account_id()
+	}
+
+	fn can_allocate(amount: u64) -> bool {
+		Charity::can_allocate(amount)
+	}
+
+	fn donation_rank(who: u128) -> Option<u32> {
+		Charity::donation_rank(&who)
+	}
+
+	fn simulate_allocation(amount: u64) -> Result<u64, DispatchError> {
+		Charity::simulate_allocation(amount)
+	}
+
+	fn causes() -> Vec<(Vec<u8>, u64)> {
+		Charity::causes()
+	}
+
+	fn pot_share_of(who: u128) -> Perbill {
+		Charity::pot_share_of(&who)
+	}
+
+	fn pending_vesting(dest: u128) -> u64 {
+		Charity::pending_vesting(&dest)
+	}
+
+	fn stats() -> charity_runtime_api::CharityStats<u64> {
+		Charity::stats()
+	}
+}
+
+parameter_types! {
+	pub static DonationsSeen: Vec<(u128, u64)> = Vec::new();
+}
+
+/// Donation handler that records every donation it is told about
+pub struct RecordDonations;
+impl OnDonationHandler<u128, u64> for RecordDonations {
+	fn on_donation(who: &u128, amount: u64) {
+		let mut seen = DonationsSeen::get();
+		seen.push((*who, amount));
+		DonationsSeen::set(seen);
+	}
+}
+
+parameter_types! {
+	pub static AllocationsSeen: Vec<(u128, u64)> = Vec::new();
+}
+
+/// Allocation handler that records every allocation it is told about
+pub struct RecordAllocations;
+impl OnAllocationHandler<u128, u64> for RecordAllocations {
+	fn on_allocation(dest: &u128, amount: u64) {
+		let mut seen = AllocationsSeen::get();
+		seen.push((*dest, amount));
+		AllocationsSeen::set(seen);
+	}
+}
+
+parameter_types! {
+	pub static RejectOddDonations: bool = false;
+	pub static DonationsValidated: Vec<(u128, u64)> = Vec::new();
+}
+
+/// Donation validator that turns away odd amounts while `RejectOddDonations` is set
+pub struct EvenDonationsOnly;
+impl ValidateDonation<u128, u64> for EvenDonationsOnly {
+	fn validate(who: &u128, amount: u64) -> DispatchResult {
+		let mut seen = DonationsValidated::get();
+		seen.push((*who, amount));
+		DonationsValidated::set(seen);
+		if RejectOddDonations::get() && amount % 2 == 1 {
+			return Err(DispatchError::Other("only even donations are accepted"));
+		}
+		Ok(())
+	}
+}
+
+parameter_types! {
+	pub static AssetBalances: BTreeMap<(u32, u128), u64> = BTreeMap::new();
+}
+
+/// A stand-in for `pallet-assets` that keeps balances of each (asset, account) pair
+pub struct MockAssets;
+impl AssetTransfer<u128> for MockAssets {
+	type AssetId = u32;
+	type Balance = u64;
+
+	fn transfer(asset: u32, source: &u128, dest: &u128, amount: u64) -> DispatchResult {
+		let mut balances = AssetBalances::get();
+		let from = balances.entry((asset, *source)).or_default();
+		*from = from
+			.checked_sub(amount)
+			.ok_or("insufficient asset balance")?;
+		*balances.entry((asset, *dest)).or_default() += amount;
+		AssetBalances::set(balances);
+		Ok(())
+	}
+
+	#[cfg(feature = "runtime-benchmarks")]
+	fn fund_for_benchmark(who: &u128, amount: u64) -> u32 {
+		let mut balances = AssetBalances::get();
+		*balances.entry((0, *who)).or_default() += amount;
+		AssetBalances::set(balances);
+		0
+	}
+}
+
+pub fn asset_balance(asset: u32, who: u128) -> u64 {
+	AssetBalances::get()
+		.get(&(asset, who))
+		.copied()
+		.unwrap_or_default()
+}
+
+parameter_types! {
+	pub static ScheduledCalls: Vec<(u64, OriginCaller, Call)> = Vec::new();
+}
+
+/// A stand-in for `pallet-scheduler` that keeps its agenda in `ScheduledCalls`
+///
+/// Nothing runs by itself; tests call `MockScheduler::on_initialize` for each block, just as the
+/// real scheduler's hook would be.
+pub struct MockScheduler;
+impl MockScheduler {
+	/// Dispatch every call scheduled for block `now`
+	pub fn on_initialize(now: u64) {
+		let (due, later) = ScheduledCalls::get()
+			.into_iter()
+			.partition::<Vec<_>, _>(|(at, _, _)| *at == now);
+		ScheduledCalls::set(later);
+		for (_, origin, call) in due {
+			let _ = call.dispatch(origin.into());
+		}
+	}
+}
+impl ScheduleAnon<u64, Call, OriginCaller> for MockScheduler {
+	type Address = u32;
+
+	fn schedule(
+		when: DispatchTime<u64>,
+		_maybe_periodic: Option<Period<u64>>,
+		_priority: Priority,
+		origin: OriginCaller,
+		call: Call,
+	) -> Result<u32, DispatchError> {
+		let at = match when {
+			DispatchTime::At(at) => at,
+			DispatchTime::After(delay) => System::block_number() + delay,
+		};
+		let mut agenda = ScheduledCalls::get();
+		agenda.push((at, origin, call));
+		ScheduledCalls::set(agenda.clone());
+		Ok(agenda.len() as u32 - 1)
+	}
+
+	fn cancel(_address: u32) -> Result<(), ()> {
+		Err(())
+	}
+
+	fn reschedule(_address: u32, _when: DispatchTime<u64>) -> Result<u32, DispatchError> {
+		Err(DispatchError::Other("the mock scheduler cannot reschedule"))
+	}
+
+	fn next_dispatch_time(address: u32) -> Result<u64, ()> {
+		ScheduledCalls::get()
+			.get(address as usize)
+			.map(|(at, _, _)| *at)
+			.ok_or(())
+	}
+}
+
+/// Builds the externalities for a test, starting from the defaults most tests share
+///
+/// The fluent methods override one piece of genesis or configuration each, e.g.
+/// `ExtBuilder::default().min_donation(5).with_initial_pot(20).build()`.
+pub struct ExtBuilder {
+	balances: Vec<(u128, u64)>,
+	initial_donations: Vec<(u128, u64)>,
+	initial_endowment: u64,
+	initial_pot: Option<u64>,
+	min_donation: u64,
+	donation_fee: Perbill,
+}
+
+impl Default for ExtBuilder {
+	fn default() -> Self {
+		Self {
+			balances: vec![(1, 13), (2, 11), (3, 1), (4, 3), (5, 19)],
+			initial_donations: vec![],
+			initial_endowment: 0,
+			initial_pot: None,
+			min_donation: 2,
+			donation_fee: Perbill::zero(),
+		}
+	}
+}
+
+impl ExtBuilder {
+	/// Endow these accounts at genesis instead of the default ones
+	pub fn with_balances(mut self, balances: Vec<(u128, u64)>) -> Self {
+		self.balances = balances;
+		self
+	}
+
+	/// Record these donations in the charity's genesis config
+	pub fn with_donations(mut self, initial_donations: Vec<(u128, u64)>) -> Self {
+		self.initial_donations = initial_donations;
+		self
+	}
+
+	/// Seed the pot with `endowment` through the charity's genesis config
+	pub fn with_endowment(mut self, endowment: u64) -> Self {
+		self.initial_endowment = endowment;
+		self
+	}
+
+	/// Start the pot off holding `pot`, rather than just its existential deposit
+	pub fn with_initial_pot(mut self, pot: u64) -> Self {
+		self.initial_pot = Some(pot);
+		self
+	}
+
+	/// Set the `MinimumDonation`
+	pub fn min_donation(mut self, min_donation: u64) -> Self {
+		self.min_donation = min_donation;
+		self
+	}
+
+	/// Set the `DonationFee`
+	pub fn donation_fee(mut self, fee: Perbill) -> Self {
+		self.donation_fee = fee;
+		self
+	}
+
+	pub fn build(self) -> sp_io::TestExternalities {
+		MinimumDonation::set(self.min_donation);
+		DonationFee::set(self.donation_fee);
+
+		// Built the same way a runtime's chain spec builds it
+		let t = GenesisConfig {
+			frame_system: Some(Default::default()),
+			pallet_balances: Some(pallet_balances::GenesisConfig {
+				balances: self.balances,
+			}),
+			charity: Some(charity::GenesisConfig {
+				initial_donations: self.initial_donations,
+				initial_endowment: self.initial_endowment,
+			}),
+			charity_Instance1: None,
+		}
+		.build_storage()
+		.unwrap();
+
+		let initial_pot = self.initial_pot;
+		let mut ext: sp_io::TestExternalities = t.into();
+		ext.execute_with(|| {
+			System::set_block_number(1);
+			if let Some(pot) = initial_pot {
+				Balances::make_free_balance_be(&Charity::account_id(), pot);
+			}
+		});
+		ext
+	}
+}
+
+/// The externalities most tests start from
+pub fn new_test_ext() -> sp_io::TestExternalities {
+	ExtBuilder::default().build()
+}
@@ -1,101 +1,20 @@
-use crate::{self as charity, Config, RawEvent};
-use frame_support::{
-	assert_err, assert_ok, construct_runtime, parameter_types,
-	traits::{Currency, OnUnbalanced},
+use crate::mock::*;
+use crate::{
+	self as charity, weights::WeightInfo, BoundedVec, CharityStats, DefaultInstance,
+	DonationReceipt, Error, ImbalanceKind, RawEvent, RemainderPolicy, StorageVersion,
 };
-use frame_system::{self as system, EventRecord, Phase, RawOrigin};
-use pallet_balances;
-use sp_core::H256;
-use sp_io;
-use sp_runtime::{
-	testing::Header,
-	traits::{BlakeTwo256, IdentityLookup},
+use frame_support::{
+	assert_err, assert_noop, assert_ok,
+	traits::{
+		Currency, ExistenceRequirement, OnFinalize, OnInitialize, OnRuntimeUpgrade, OnUnbalanced,
+	},
+	weights::WithPostDispatchInfo,
+	StorageMap, StorageValue,
 };
-
-type UncheckedExtrinsic = frame_system::mocking::MockUncheckedExtrinsic<TestRuntime>;
-type Block = frame_system::mocking::MockBlock<TestRuntime>;
-
-construct_runtime!(
-	pub enum TestRuntime where
-		Block = Block,
-		NodeBlock = Block,
-		UncheckedExtrinsic = UncheckedExtrinsic,
-	{
-		System: frame_system::{Module, Call, Config, Storage, Event<T>},
-		Balances: pallet_balances::{Module, Call, Storage, Config<T>, Event<T>},
-		Charity: charity::{Module, Call, Storage, Event<T>},
-	}
-);
-
-parameter_types! {
-	pub const BlockHashCount: u64 = 250;
-	pub BlockWeights: frame_system::limits::BlockWeights =
-		frame_system::limits::BlockWeights::simple_max(1024);
-}
-impl frame_system::Config for TestRuntime {
-	type BaseCallFilter = ();
-	type BlockWeights = ();
-	type BlockLength = ();
-	type Origin = Origin;
-	type Index = u64;
-	type Call = Call;
-	type BlockNumber = u64;
-	type Hash = H256;
-	type Hashing = BlakeTwo256;
-	type AccountId = u64;
-	type Lookup = IdentityLookup<Self::AccountId>;
-	type Header = Header;
-	type Event = Event;
-	type BlockHashCount = BlockHashCount;
-	type DbWeight = ();
-	type Version = ();
-	type PalletInfo = PalletInfo;
-	type AccountData = pallet_balances::AccountData<u64>;
-	type OnNewAccount = ();
-	type OnKilledAccount = ();
-	type SystemWeightInfo = ();
-	type SS58Prefix = ();
-}
-
-parameter_types! {
-	pub const ExistentialDeposit: u64 = 1;
-}
-impl pallet_balances::Config for TestRuntime {
-	type MaxLocks = ();
-	type Balance = u64;
-	type Event = Event;
-	type DustRemoval = ();
-	type ExistentialDeposit = ExistentialDeposit;
-	type AccountStore = System;
-	type WeightInfo = ();
-}
-
-impl Config for TestRuntime {
-	type Event = Event;
-	type Currency = Balances;
-}
-
-// An alternative to `ExternalityBuilder` which includes custom configuration
-pub fn new_test_ext() -> sp_io::TestExternalities {
-	let mut t = system::GenesisConfig::default()
-		.build_storage::<TestRuntime>()
-		.unwrap();
-
-	pallet_balances::GenesisConfig::<TestRuntime> {
-		// Provide some initial balances
-		balances: vec![(1, 13), (2, 11), (3, 1), (4, 3), (5, 19)],
-	}
-	.assimilate_storage(&mut t)
-	.unwrap();
-
-	crate::GenesisConfig {}
-		.assimilate_storage::<TestRuntime>(&mut t)
-		.unwrap();
-
-	let mut ext: sp_io::TestExternalities = t.into();
-	ext.execute_with(|| System::set_block_number(1));
-	ext
-}
+use frame_system::{EventRecord, Phase, RawOrigin};
+use parity_scale_codec::{Decode, Encode};
+use sp_runtime::{traits::AccountIdConversion, ModuleId, Perbill};
+use std::convert::TryInto;
 
 /// Charity pot minimum balance is set
 #[test]
@@ -113,13 +32,54 @@ fn new_test_ext_behaves() {
 	})
 }
 
+#[test]
+fn ext_builder_sets_min_donation() {
+	ExtBuilder::default()
+		.min_donation(5)
+		.build()
+		.execute_with(|| {
+			assert_noop!(
+				Charity::donate(Origin::signed(1), 3, vec![], None),
+				Error::<TestRuntime, DefaultInstance>::DonationTooSmall
+			);
+			assert_ok!(Charity::donate(Origin::signed(1), 5, vec![], None));
+		})
+}
+
+#[test]
+fn ext_builder_sets_balances_and_pot() {
+	ExtBuilder::default()
+		.with_balances(vec![(7, 50)])
+		.with_initial_pot(20)
+		.build()
+		.execute_with(|| {
+			assert_eq!(Charity::pot(), 20);
+			assert_eq!(Balances::free_balance(&7), 50);
+			assert_eq!(Balances::free_balance(&1), 0);
+
+			assert_ok!(Charity::donate(Origin::signed(7), 10, vec![], None));
+			assert_eq!(Charity::pot(), 30);
+		})
+}
+
+#[test]
+fn ext_builder_sets_donation_fee() {
+	ExtBuilder::default()
+		.donation_fee(Perbill::from_percent(10))
+		.build()
+		.execute_with(|| {
+			assert_ok!(Charity::donate(Origin::signed(1), 10, vec![], None));
+			assert_eq!(Charity::pot(), Balances::minimum_balance() + 9);
+		})
+}
+
 #[test]
 fn donations_work() {
 	new_test_ext().execute_with(|| {
 		// User 1 donates 10 of her 13 tokens
 		let original = Balances::free_balance(&1);
 		let donation = 10;
-		assert_ok!(Charity::donate(Origin::signed(1), donation));
+		assert_ok!(Charity::donate(Origin::signed(1), donation, vec![], None));
 
 		// Charity should have 10 tokens
 		let new_pot_total = Balances::minimum_balance() + donation;
@@ -129,19 +89,373 @@ fn donations_work() {
 		assert_eq!(Balances::free_balance(&1), original - donation);
 
 		// Check that the correct event is emitted
-		let expected_event = Event::charity(RawEvent::DonationReceived(1, donation, new_pot_total));
+		let expected_event = Event::charity(RawEvent::DonationReceived(
+			1,
+			donation,
+			donation,
+			new_pot_total,
+			Default::default(),
+			1,
+			donation,
+			0,
+		));
 
 		assert_eq!(System::events()[1].event, expected_event,);
 	})
 }
 
+#[test]
+fn donate_all_leaves_the_donor_at_the_existential_deposit() {
+	new_test_ext().execute_with(|| {
+		let ed = Balances::minimum_balance();
+		let spare = Balances::free_balance(&5) - ed;
+
+		assert_ok!(Charity::donate_all(Origin::signed(5)));
+		assert_eq!(Balances::free_balance(&5), ed);
+		assert_eq!(Charity::pot(), ed + spare);
+		// It is attributed like any other donation
+		assert_eq!(Charity::donation_of(5), spare);
+		assert_eq!(Charity::total_donated(), spare);
+	})
+}
+
+#[test]
+fn donate_all_rounds_down_to_whole_units() {
+	new_test_ext().execute_with(|| {
+		DonationUnit::set(5);
+
+		// Account 5 can spare 18, of which 15 is whole units
+		assert_ok!(Charity::donate_all(Origin::signed(5)));
+		assert_eq!(Charity::donation_of(5), 15);
+		assert_eq!(Balances::free_balance(&5), Balances::minimum_balance() + 3);
+	})
+}
+
+#[test]
+fn donate_all_with_nothing_to_spare_fails() {
+	new_test_ext().execute_with(|| {
+		// Account 3 holds exactly the existential deposit
+		assert_noop!(
+			Charity::donate_all(Origin::signed(3)),
+			Error::<TestRuntime, DefaultInstance>::DonationTooSmall
+		);
+	})
+}
+
+#[test]
+fn total_donated_tracks_lifetime_donations() {
+	new_test_ext().execute_with(|| {
+		assert_eq!(Charity::total_donated(), 0);
+
+		assert_ok!(Charity::donate(Origin::signed(1), 10, vec![], None));
+		assert_ok!(Charity::donate(Origin::signed(2), 7, vec![], None));
+		assert_eq!(Charity::total_donated(), 17);
+
+		// Allocations shrink the pot but not the lifetime total
+		assert_ok!(Charity::allocate(RawOrigin::Root.into(), 3, 12));
+		assert_eq!(Charity::pot(), 6);
+		assert_eq!(Charity::total_donated(), 17);
+	})
+}
+
+#[test]
+fn donations_are_recorded_per_donor() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Charity::donate(Origin::signed(1), 4, vec![], None));
+		assert_ok!(Charity::donate(Origin::signed(1), 6, vec![], None));
+
+		assert_eq!(Charity::donation_of(1), 10);
+		// Someone who never donated reads as zero
+		assert_eq!(Charity::donation_of(2), 0);
+	})
+}
+
+#[test]
+fn partial_withdrawal_works() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Charity::donate(Origin::signed(1), 10, vec![], None));
+		assert_ok!(Charity::withdraw_donation(Origin::signed(1), 4));
+
+		assert_eq!(Balances::free_balance(1), 7);
+		assert_eq!(Charity::donation_of(1), 6);
+		assert_eq!(Charity::total_donated(), 6);
+		assert_eq!(Charity::pot(), 7);
+
+		assert_eq!(
+			System::events().last().unwrap().event,
+			Event::charity(RawEvent::DonationWithdrawn(1, 4, 7))
+		);
+	})
+}
+
+#[test]
+fn full_withdrawal_clears_record() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Charity::donate(Origin::signed(1), 10, vec![], None));
+		assert_ok!(Charity::withdraw_donation(Origin::signed(1), 10));
+
+		assert_eq!(Balances::free_balance(1), 13);
+		assert_eq!(Charity::donation_of(1), 0);
+		assert!(!charity::Donations::<TestRuntime>::contains_key(1));
+		// The pot keeps its existential deposit
+		assert_eq!(Charity::pot(), Balances::minimum_balance());
+	})
+}
+
+#[test]
+fn cant_withdraw_more_than_contributed() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(
+			Charity::withdraw_donation(Origin::signed(1), 1),
+			Error::<TestRuntime, DefaultInstance>::NothingToWithdraw
+		);
+
+		assert_ok!(Charity::donate(Origin::signed(1), 5, vec![], None));
+		assert_noop!(
+			Charity::withdraw_donation(Origin::signed(1), 6),
+			Error::<TestRuntime, DefaultInstance>::ExceedsContribution
+		);
+	})
+}
+
+#[test]
+fn cant_withdraw_allocated_funds() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Charity::donate(Origin::signed(1), 10, vec![], None));
+		assert_ok!(Charity::allocate(RawOrigin::Root.into(), 2, 8));
+
+		// Only 2 of the donation remain above the pot's existential deposit
+		assert_noop!(
+			Charity::withdraw_donation(Origin::signed(1), 3),
+			Error::<TestRuntime, DefaultInstance>::InsufficientPot
+		);
+		assert_ok!(Charity::withdraw_donation(Origin::signed(1), 2));
+		assert_eq!(Charity::pot(), Balances::minimum_balance());
+	})
+}
+
+#[test]
+fn donation_at_minimum_works() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Charity::donate(
+			Origin::signed(1),
+			MinimumDonation::get(),
+			vec![],
+			None
+		));
+		assert_eq!(Charity::pot(), 3);
+	})
+}
+
+#[test]
+fn donation_below_minimum_fails() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(
+			Charity::donate(Origin::signed(1), MinimumDonation::get() - 1, vec![], None),
+			Error::<TestRuntime, DefaultInstance>::DonationTooSmall
+		);
+		assert_eq!(Charity::pot(), Balances::minimum_balance());
+		assert!(System::events().is_empty());
+	})
+}
+
+#[test]
+fn donations_in_whole_units_are_accepted() {
+	new_test_ext().execute_with(|| {
+		DonationUnit::set(5);
+		assert_ok!(Charity::donate(Origin::signed(1), 10, vec![], None));
+		assert_eq!(Charity::pot(), Balances::minimum_balance() + 10);
+	})
+}
+
+#[test]
+fn donations_that_are_not_a_unit_multiple_fail() {
+	new_test_ext().execute_with(|| {
+		DonationUnit::set(5);
+		assert_noop!(
+			Charity::donate(Origin::signed(1), 7, vec![], None),
+			Error::<TestRuntime, DefaultInstance>::NotUnitMultiple
+		);
+		assert_noop!(
+			Charity::schedule_donation(Origin::signed(1), 7, 3),
+			Error::<TestRuntime, DefaultInstance>::NotUnitMultiple
+		);
+		assert_eq!(Charity::pot(), Balances::minimum_balance());
+	})
+}
+
+#[test]
+fn a_unit_of_one_accepts_any_amount() {
+	new_test_ext().execute_with(|| {
+		assert_eq!(DonationUnit::get(), 1);
+		assert_ok!(Charity::donate(Origin::signed(1), 7, vec![], None));
+		assert_eq!(Charity::pot(), Balances::minimum_balance() + 7);
+	})
+}
+
+#[test]
+fn zero_donation_fee_sends_everything_to_the_pot() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Charity::donate(Origin::signed(1), 10, vec![], None));
+
+		assert_eq!(Charity::pot(), 11);
+		assert_eq!(Charity::donation_of(1), 10);
+		assert_eq!(Balances::free_balance(FeeCollector::get()), 0);
+		assert!(System::events().iter().any(|r| r.event
+			== Event::charity(RawEvent::DonationReceived(
+				1,
+				10,
+				10,
+				11,
+				Default::default(),
+				1,
+				10,
+				0
+			))));
+		assert!(!System::events()
+			.iter()
+			.any(|r| matches!(r.event, Event::charity(RawEvent::DonationFeeCharged(..)))));
+	})
+}
+
+#[test]
+fn donation_fee_is_skimmed_to_the_collector() {
+	new_test_ext().execute_with(|| {
+		DonationFee::set(Perbill::from_percent(25));
+
+		// A quarter of 10 is 2.5, which rounds down to 2
+		assert_ok!(Charity::donate(Origin::signed(1), 10, vec![], None));
+		assert_eq!(Balances::free_balance(1), 3);
+		assert_eq!(Balances::free_balance(FeeCollector::get()), 2);
+		assert_eq!(Charity::pot(), 1 + 8);
+		assert_eq!(Charity::donation_of(1), 8);
+		assert_eq!(Charity::total_donated(), 8);
+		assert!(System::events()
+			.iter()
+			.any(|r| r.event == Event::charity(RawEvent::DonationFeeCharged(1, 2))));
+
+		assert!(System::events().iter().any(|r| r.event
+			== Event::charity(RawEvent::DonationReceived(
+				1,
+				10,
+				8,
+				9,
+				Default::default(),
+				1,
+				8,
+				0
+			))));
+
+		// A quarter of 7 is 1.75, which rounds up to 2
+		assert_ok!(Charity::donate(Origin::signed(2), 7, vec![], None));
+		assert_eq!(Balances::free_balance(FeeCollector::get()), 4);
+		assert_eq!(Charity::pot(), 1 + 8 + 5);
+		assert_eq!(Charity::donation_of(2), 5);
+	})
+}
+
+#[test]
+fn donations_get_sequential_receipts() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(3);
+		assert_ok!(Charity::donate(Origin::signed(1), 10, vec![], None));
+		System::set_block_number(4);
+		assert_ok!(Charity::donate(Origin::signed(2), 4, vec![], None));
+
+		assert_eq!(
+			Charity::receipt(0),
+			Some(DonationReceipt {
+				donor: 1,
+				amount: 10,
+				block: 3
+			})
+		);
+		assert_eq!(
+			Charity::receipt(1),
+			Some(DonationReceipt {
+				donor: 2,
+				amount: 4,
+				block: 4
+			})
+		);
+		assert_eq!(Charity::receipt(2), None);
+		assert_eq!(Charity::next_receipt_id(), 2);
+		assert!(System::events().iter().any(|r| r.event
+			== Event::charity(RawEvent::DonationReceived(
+				2,
+				4,
+				4,
+				15,
+				Default::default(),
+				2,
+				4,
+				1
+			))));
+	})
+}
+
+#[test]
+fn donation_memo_appears_in_event() {
+	new_test_ext().execute_with(|| {
+		let memo = b"In memory of X".to_vec();
+		assert_ok!(Charity::donate(Origin::signed(1), 5, memo.clone(), None));
+
+		assert_eq!(
+			System::events()[1].event,
+			Event::charity(RawEvent::DonationReceived(
+				1,
+				5,
+				5,
+				6,
+				memo.try_into().unwrap(),
+				1,
+				5,
+				0
+			))
+		);
+	})
+}
+
+#[test]
+fn oversized_memo_is_rejected() {
+	new_test_ext().execute_with(|| {
+		let memo = vec![0u8; MaxMemoLength::get() as usize + 1];
+		assert_noop!(
+			Charity::donate(Origin::signed(1), 5, memo, None),
+			Error::<TestRuntime, DefaultInstance>::MemoTooLong
+		);
+		assert_eq!(Charity::pot(), Balances::minimum_balance());
+	})
+}
+
+#[test]
+fn memo_of_the_maximum_length_is_accepted() {
+	new_test_ext().execute_with(|| {
+		let memo = vec![0u8; MaxMemoLength::get() as usize];
+		assert_ok!(Charity::donate(Origin::signed(1), 5, memo.clone(), None));
+		assert_eq!(
+			System::events()[1].event,
+			Event::charity(RawEvent::DonationReceived(
+				1,
+				5,
+				5,
+				6,
+				memo.try_into().unwrap(),
+				1,
+				5,
+				0
+			))
+		);
+	})
+}
+
 #[test]
 fn cant_donate_too_much() {
 	new_test_ext().execute_with(|| {
 		// User 1 donates 20 toekns but only has 13
-		assert_err!(
-			Charity::donate(Origin::signed(1), 20),
-			"Can't make donation"
+		assert_noop!(
+			Charity::donate(Origin::signed(1), 20, vec![], None),
+			Error::<TestRuntime, DefaultInstance>::InsufficientFunds
 		);
 	})
 }
@@ -161,7 +475,11 @@ fn imbalances_work() {
 			System::events()[0],
 			EventRecord {
 				phase: Phase::Initialization,
-				event: Event::charity(RawEvent::ImbalanceAbsorbed(5, new_pot_total)),
+				event: Event::charity(RawEvent::ImbalanceAbsorbed(
+					5,
+					new_pot_total,
+					ImbalanceKind::Unknown
+				)),
 				topics: vec![],
 			},
 		);
@@ -173,7 +491,7 @@ fn allocating_works() {
 	new_test_ext().execute_with(|| {
 		// Charity acquires 10 tokens from user 1
 		let donation = 10;
-		assert_ok!(Charity::donate(Origin::signed(1), donation));
+		assert_ok!(Charity::donate(Origin::signed(1), donation, vec![], None));
 
 		// Charity allocates 5 tokens to user 2
 		let alloc = 5;
@@ -193,24 +511,3366 @@ fn allocating_works() {
 			.collect::<Vec<_>>();
 
 		let expected_events = vec![
-			RawEvent::DonationReceived(1, 10, 11),
+			RawEvent::DonationReceived(1, 10, 10, 11, Default::default(), 1, 10, 0),
+			RawEvent::NewRecordDonation(1, 10),
+			RawEvent::RankChanged(1, None, Some(1)),
 			RawEvent::FundsAllocated(2, 5, 6),
 		];
 
 		assert_eq!(our_events, expected_events);
 	})
 }
-//TODO What if we try to allocate more funds than we have
+
 #[test]
-fn cant_allocate_too_much() {
+fn total_allocated_balances_the_books() {
 	new_test_ext().execute_with(|| {
-		// Charity acquires 10 tokens from user 1
-		assert_ok!(Charity::donate(Origin::signed(1), 10));
+		assert_ok!(Charity::donate(Origin::signed(1), 10, vec![], None));
+		assert_ok!(Charity::donate(Origin::signed(5), 15, vec![], None));
+		Charity::on_nonzero_unbalanced(pallet_balances::NegativeImbalance::new(4));
 
-		// Charity tries to allocates 20 tokens to user 2
-		assert_err!(
-			Charity::allocate(RawOrigin::Root.into(), 2, 20),
-			"Can't make allocation"
+		assert_ok!(Charity::allocate(RawOrigin::Root.into(), 2, 5));
+		assert_ok!(Charity::allocate_batch(
+			RawOrigin::Root.into(),
+			vec![(3, 2), (4, 3)]
+		));
+		assert_ok!(Charity::allocate_with_vesting(
+			RawOrigin::Root.into(),
+			2,
+			6,
+			3,
+			2
+		));
+		run_to_block(2);
+		assert_eq!(Charity::total_allocated(), 5 + 2 + 3 + 3);
+
+		// What came in, less what went out, is what the pot holds besides its locked funds
+		let imbalances = 4;
+		assert_eq!(
+			Charity::pot() + Charity::locked_funds(),
+			Balances::minimum_balance() + Charity::total_donated() + imbalances
+				- Charity::total_allocated()
+		);
+	})
+}
+
+#[test]
+fn cant_allocate_to_the_pot_itself() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Charity::donate(Origin::signed(1), 10, vec![], None));
+		System::reset_events();
+
+		assert_noop!(
+			Charity::allocate(RawOrigin::Root.into(), Charity::account_id(), 5),
+			Error::<TestRuntime, DefaultInstance>::CannotAllocateToSelf
+				.with_weight(<() as WeightInfo>::allocate_noop())
+		);
+		assert_eq!(Charity::pot(), 11);
+		assert!(System::events().is_empty());
+	})
+}
+
+#[test]
+fn no_allocation_route_pays_the_pot_itself() {
+	new_test_ext().execute_with(|| {
+		let pot = Charity::account_id();
+		assert_ok!(Charity::donate_to_cause(
+			Origin::signed(1),
+			b"education".to_vec(),
+			10
+		));
+		System::reset_events();
+
+		assert_noop!(
+			Charity::allocate_all(RawOrigin::Root.into(), pot, false),
+			Error::<TestRuntime, DefaultInstance>::CannotAllocateToSelf
+		);
+		assert_noop!(
+			Charity::allocate_from_cause(RawOrigin::Root.into(), b"education".to_vec(), pot, 5),
+			Error::<TestRuntime, DefaultInstance>::CannotAllocateToSelf
+		);
+		assert_noop!(
+			Charity::allocate_proportional(
+				RawOrigin::Root.into(),
+				vec![
+					(2, Perbill::from_percent(50)),
+					(pot, Perbill::from_percent(50))
+				],
+				RemainderPolicy::KeepInPot,
+			),
+			Error::<TestRuntime, DefaultInstance>::CannotAllocateToSelf
+		);
+		assert_noop!(
+			Charity::propose_allocation(RawOrigin::Root.into(), pot, 5),
+			Error::<TestRuntime, DefaultInstance>::CannotAllocateToSelf
+		);
+		assert_eq!(Charity::pot(), 11);
+
+		// A batch skips the payment to the pot but makes the others
+		assert_ok!(Charity::allocate_batch(
+			RawOrigin::Root.into(),
+			vec![(pot, 5), (2, 3)]
+		));
+		assert_eq!(Charity::pot(), 8);
+		assert!(System::events()
+			.iter()
+			.any(|r| r.event == Event::charity(RawEvent::AllocationFailed(pot, 5))));
+	})
+}
+
+#[test]
+fn allocating_nothing_is_a_cheap_no_op() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Charity::donate(Origin::signed(1), 10, vec![], None));
+		System::reset_events();
+
+		let info = Charity::allocate(RawOrigin::Root.into(), 2, 0).unwrap();
+		assert_eq!(
+			info.actual_weight,
+			Some(<() as WeightInfo>::allocate_noop())
+		);
+		assert!(<() as WeightInfo>::allocate_noop() < <() as WeightInfo>::allocate());
+		assert_eq!(Charity::pot(), 11);
+		assert!(System::events().is_empty());
+
+		// A real allocation is charged in full
+		let info = Charity::allocate(RawOrigin::Root.into(), 2, 5).unwrap();
+		assert_eq!(info.actual_weight, None);
+	})
+}
+
+#[test]
+fn rejected_self_allocations_are_charged_as_no_ops() {
+	new_test_ext().execute_with(|| {
+		let err = Charity::allocate(RawOrigin::Root.into(), Charity::account_id(), 5).unwrap_err();
+		assert_eq!(
+			err.post_info.actual_weight,
+			Some(<() as WeightInfo>::allocate_noop())
+		);
+	})
+}
+
+#[test]
+fn allocating_to_other_accounts_still_works() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Charity::donate(Origin::signed(1), 10, vec![], None));
+
+		assert_ok!(Charity::allocate(RawOrigin::Root.into(), 3, 5));
+		assert_eq!(Balances::free_balance(3), 1 + 5);
+		assert_eq!(Charity::pot(), 6);
+	})
+}
+
+#[test]
+fn allocations_may_empty_the_pot_under_allow_death() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Charity::donate(Origin::signed(1), 10, vec![], None));
+
+		assert_ok!(Charity::allocate(RawOrigin::Root.into(), 3, 11));
+		assert_eq!(Balances::free_balance(3), 1 + 11);
+		assert_eq!(Charity::pot(), 0);
+	})
+}
+
+#[test]
+fn allocations_must_keep_the_pot_alive_under_keep_alive() {
+	new_test_ext().execute_with(|| {
+		AllocationExistenceRequirement::set(ExistenceRequirement::KeepAlive);
+		assert_ok!(Charity::donate(Origin::signed(1), 10, vec![], None));
+
+		assert_noop!(
+			Charity::allocate(RawOrigin::Root.into(), 3, 11),
+			pallet_balances::Error::<TestRuntime>::KeepAlive
+		);
+		assert_ok!(Charity::allocate(RawOrigin::Root.into(), 3, 10));
+		assert_eq!(Charity::pot(), Balances::minimum_balance());
+	})
+}
+
+#[test]
+fn allocations_may_leave_exactly_the_reserve() {
+	new_test_ext().execute_with(|| {
+		MinPotReserve::set(4);
+		assert_ok!(Charity::donate(Origin::signed(1), 10, vec![], None));
+
+		assert_ok!(Charity::allocate(RawOrigin::Root.into(), 2, 7));
+		assert_eq!(Charity::pot(), 4);
+	})
+}
+
+#[test]
+fn allocations_cannot_breach_the_reserve() {
+	new_test_ext().execute_with(|| {
+		MinPotReserve::set(4);
+		assert_ok!(Charity::donate(Origin::signed(1), 10, vec![], None));
+
+		assert_noop!(
+			Charity::allocate(RawOrigin::Root.into(), 2, 8),
+			Error::<TestRuntime, DefaultInstance>::WouldBreachReserve
+		);
+
+		// Batched payments are held to the reserve one at a time
+		assert_ok!(Charity::allocate_batch(
+			RawOrigin::Root.into(),
+			vec![(2, 5), (3, 3)]
+		));
+		assert_eq!(Balances::free_balance(2), 11 + 5);
+		assert_eq!(Balances::free_balance(3), 1);
+		assert_eq!(Charity::pot(), 6);
+	})
+}
+
+#[test]
+fn allocate_all_leaves_the_reserve_behind() {
+	new_test_ext().execute_with(|| {
+		MinPotReserve::set(4);
+		assert_ok!(Charity::donate(Origin::signed(1), 10, vec![], None));
+
+		assert_ok!(Charity::allocate_all(RawOrigin::Root.into(), 2, false));
+		assert_eq!(Balances::free_balance(2), 11 + 7);
+		assert_eq!(Charity::pot(), 4);
+
+		assert_noop!(
+			Charity::allocate_all(RawOrigin::Root.into(), 2, false),
+			Error::<TestRuntime, DefaultInstance>::InsufficientPot
+		);
+	})
+}
+
+#[test]
+fn every_allocation_route_keeps_the_reserve() {
+	new_test_ext().execute_with(|| {
+		MinPotReserve::set(4);
+		assert_ok!(Charity::donate_to_cause(
+			Origin::signed(1),
+			b"education".to_vec(),
+			10
+		));
+
+		assert_noop!(
+			Charity::allocate_from_cause(RawOrigin::Root.into(), b"education".to_vec(), 2, 8),
+			Error::<TestRuntime, DefaultInstance>::WouldBreachReserve
+		);
+		assert_noop!(
+			Charity::allocate_proportional(
+				RawOrigin::Root.into(),
+				vec![
+					(2, Perbill::from_percent(50)),
+					(3, Perbill::from_percent(30))
+				],
+				RemainderPolicy::KeepInPot,
+			),
+			Error::<TestRuntime, DefaultInstance>::WouldBreachReserve
 		);
+		assert_noop!(
+			Charity::propose_allocation(RawOrigin::Root.into(), 2, 8),
+			Error::<TestRuntime, DefaultInstance>::WouldBreachReserve
+		);
+		assert_noop!(
+			Charity::allocate_with_vesting(RawOrigin::Root.into(), 2, 8, 2, 1),
+			Error::<TestRuntime, DefaultInstance>::WouldBreachReserve
+		);
+		assert_eq!(Charity::pot(), 11);
 	})
 }
+
+#[test]
+fn zero_reserve_lets_allocations_empty_the_pot() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Charity::donate(Origin::signed(1), 10, vec![], None));
+
+		assert_ok!(Charity::allocate(RawOrigin::Root.into(), 2, 11));
+		assert_eq!(Charity::pot(), 0);
+	})
+}
+
+#[test]
+fn cant_allocate_too_much() {
+	new_test_ext().execute_with(|| {
+		// Charity acquires 10 tokens from user 1
+		assert_ok!(Charity::donate(Origin::signed(1), 10, vec![], None));
+
+		// Charity tries to allocates 20 tokens to user 2
+		assert_err!(
+			Charity::allocate(RawOrigin::Root.into(), 2, 20),
+			Error::<TestRuntime, DefaultInstance>::InsufficientPot
+		);
+
+		// Only the shortfall is reported for the failed call
+		assert_eq!(Charity::pot(), 11);
+		assert_eq!(
+			System::events().last().unwrap().event,
+			Event::charity(RawEvent::AllocationShortfall(20, 11, 9))
+		);
+		assert!(!System::events()
+			.iter()
+			.any(|r| matches!(r.event, Event::charity(RawEvent::FundsAllocated(..)))));
+	})
+}
+
+#[test]
+fn allocation_shortfall_is_measured_against_the_allocatable_pot() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Charity::donate(Origin::signed(1), 10, vec![], None));
+		assert_ok!(Charity::lock_for_proposal(6));
+
+		assert_err!(
+			Charity::allocate(RawOrigin::Root.into(), 2, 8),
+			Error::<TestRuntime, DefaultInstance>::InsufficientPot
+		);
+		assert_eq!(
+			System::events().last().unwrap().event,
+			Event::charity(RawEvent::AllocationShortfall(8, 5, 3))
+		);
+	})
+}
+
+#[test]
+fn allocation_without_a_fee_pays_the_recipient_in_full() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Charity::donate(Origin::signed(1), 10, vec![], None));
+
+		assert_ok!(Charity::allocate(RawOrigin::Root.into(), 3, 10));
+		assert_eq!(Balances::free_balance(3), 1 + 10);
+		assert_eq!(Balances::free_balance(OperationsAccount::get()), 0);
+		assert!(!System::events()
+			.iter()
+			.any(|r| matches!(r.event, Event::charity(RawEvent::AllocationFeeCharged(..)))));
+	})
+}
+
+#[test]
+fn allocation_fee_funds_operations() {
+	new_test_ext().execute_with(|| {
+		AllocationFee::set(Perbill::from_percent(20));
+		assert_ok!(Charity::donate(Origin::signed(1), 10, vec![], None));
+
+		assert_ok!(Charity::allocate(RawOrigin::Root.into(), 3, 10));
+		let fee = Balances::free_balance(OperationsAccount::get());
+		let paid = Balances::free_balance(3) - 1;
+		assert_eq!((fee, paid), (2, 8));
+		// The split accounts for the whole allocation
+		assert_eq!(fee + paid, 10);
+		assert_eq!(Charity::pot(), 1);
+		assert_eq!(Charity::total_allocated(), 10);
+
+		let events = System::events();
+		assert!(events
+			.iter()
+			.any(|r| r.event == Event::charity(RawEvent::AllocationFeeCharged(3, 2))));
+		assert_eq!(
+			events.last().unwrap().event,
+			Event::charity(RawEvent::FundsAllocated(3, 8, 1))
+		);
+	})
+}
+
+#[test]
+fn every_allocation_route_pays_the_fee() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		AllocationFee::set(Perbill::from_percent(20));
+		assert_ok!(Charity::donate(Origin::signed(5), 15, vec![], None));
+
+		assert_ok!(Charity::allocate_batch(
+			RawOrigin::Root.into(),
+			vec![(2, 5)]
+		));
+		assert_eq!(Balances::free_balance(2), 11 + 4);
+
+		// A queued allocation is charged when it is paid
+		assert_ok!(Charity::propose_allocation(RawOrigin::Root.into(), 3, 5));
+		run_to_block(4);
+		assert_eq!(Balances::free_balance(3), 1 + 4);
+
+		assert_ok!(Charity::allocate_all(RawOrigin::Root.into(), 4, true));
+		assert_eq!(Balances::free_balance(4), 3 + 4);
+
+		assert_eq!(Balances::free_balance(OperationsAccount::get()), 3);
+		assert_eq!(Charity::pot(), 1);
+		assert_eq!(Charity::total_allocated(), 15);
+	})
+}
+
+#[test]
+fn proportional_allocation_splits_pot() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Charity::donate(Origin::signed(1), 11, vec![], None));
+		assert_eq!(Charity::pot(), 12);
+
+		assert_ok!(Charity::allocate_proportional(
+			RawOrigin::Root.into(),
+			vec![
+				(2, Perbill::from_percent(50)),
+				(3, Perbill::from_percent(50))
+			],
+			RemainderPolicy::KeepInPot
+		));
+
+		assert_eq!(Balances::free_balance(2), 11 + 6);
+		assert_eq!(Balances::free_balance(3), 1 + 6);
+		assert_eq!(Charity::pot(), 0);
+
+		let our_events = System::events()
+			.into_iter()
+			.filter_map(|r| match r.event {
+				Event::charity(RawEvent::FundsAllocated(dest, amount, pot)) => {
+					Some((dest, amount, pot))
+				}
+				_ => None,
+			})
+			.collect::<Vec<_>>();
+		assert_eq!(our_events, vec![(2, 6, 6), (3, 6, 0)]);
+	})
+}
+
+#[test]
+fn proportional_allocation_leaves_remainder_in_pot() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Charity::donate(Origin::signed(1), 10, vec![], None));
+		assert_eq!(Charity::pot(), 11);
+
+		let third = Perbill::from_rational_approximation(1u32, 3u32);
+		assert_ok!(Charity::allocate_proportional(
+			RawOrigin::Root.into(),
+			vec![(2, third), (3, third), (4, third)],
+			RemainderPolicy::KeepInPot
+		));
+
+		// Each third of 11 rounds down to 3, so 2 stay behind
+		assert_eq!(Balances::free_balance(2), 11 + 3);
+		assert_eq!(Balances::free_balance(3), 1 + 3);
+		assert_eq!(Balances::free_balance(4), 3 + 3);
+		assert_eq!(Charity::pot(), 2);
+	})
+}
+
+#[test]
+fn proportional_allocation_can_give_dust_to_the_first_recipient() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Charity::donate(Origin::signed(1), 10, vec![], None));
+
+		let third = Perbill::from_rational_approximation(1u32, 3u32);
+		assert_ok!(Charity::allocate_proportional(
+			RawOrigin::Root.into(),
+			vec![(2, third), (3, third), (4, third)],
+			RemainderPolicy::GiveToFirst
+		));
+
+		// The thirds add up to just under the whole pot, which rounds down to 10
+		assert_eq!(Balances::free_balance(2), 11 + 3 + 1);
+		assert_eq!(Balances::free_balance(3), 1 + 3);
+		assert_eq!(Balances::free_balance(4), 3 + 3);
+		// 4 + 3 + 3 disbursed
+		assert_eq!(Charity::pot(), 1);
+	})
+}
+
+#[test]
+fn proportional_allocation_can_give_dust_to_the_last_recipient() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Charity::donate(Origin::signed(1), 10, vec![], None));
+
+		// 30% and 45% of 11 are 3.3 and 4.95; 75% is 8.25
+		assert_ok!(Charity::allocate_proportional(
+			RawOrigin::Root.into(),
+			vec![
+				(2, Perbill::from_percent(30)),
+				(3, Perbill::from_percent(45))
+			],
+			RemainderPolicy::GiveToLast
+		));
+
+		assert_eq!(Balances::free_balance(2), 11 + 3);
+		assert_eq!(Balances::free_balance(3), 1 + 4 + 1);
+		// 3 + 5 disbursed, and the unclaimed quarter kept
+		assert_eq!(Charity::pot(), 3);
+	})
+}
+
+#[test]
+fn proportional_allocation_rounds_the_dust_down() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Charity::donate(Origin::signed(1), 10, vec![], None));
+
+		// 45% of 11 is 4.95 each, and 90% is 9.9: rounding to the nearest would hand out 10
+		assert_ok!(Charity::allocate_proportional(
+			RawOrigin::Root.into(),
+			vec![
+				(2, Perbill::from_percent(45)),
+				(3, Perbill::from_percent(45))
+			],
+			RemainderPolicy::GiveToLast
+		));
+
+		assert_eq!(Balances::free_balance(2), 11 + 4);
+		assert_eq!(Balances::free_balance(3), 1 + 4 + 1);
+		assert_eq!(Charity::pot(), 2);
+	})
+}
+
+#[test]
+fn proportional_allocation_rejects_excess_shares() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Charity::donate(Origin::signed(1), 10, vec![], None));
+
+		assert_noop!(
+			Charity::allocate_proportional(
+				RawOrigin::Root.into(),
+				vec![
+					(2, Perbill::from_percent(60)),
+					(3, Perbill::from_percent(50))
+				],
+				RemainderPolicy::KeepInPot
+			),
+			Error::<TestRuntime, DefaultInstance>::InvalidShares
+		);
+		assert_eq!(Charity::pot(), 11);
+	})
+}
+
+#[test]
+fn proportional_allocation_rejects_oversized_batches_first() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Charity::donate(Origin::signed(1), 10, vec![], None));
+		assert_ok!(Charity::pause(RawOrigin::Root.into()));
+
+		// The length is checked before the pause or the shares
+		let recipients = vec![(2, Perbill::from_percent(30)); MaxBatchSize::get() as usize + 1];
+		assert_noop!(
+			Charity::allocate_proportional(
+				RawOrigin::Root.into(),
+				recipients,
+				RemainderPolicy::KeepInPot
+			),
+			Error::<TestRuntime, DefaultInstance>::BatchTooLarge
+		);
+		assert_eq!(Charity::pot(), 11);
+	})
+}
+
+#[test]
+fn runtime_api_reports_pot_balance() {
+	use charity_runtime_api::runtime_decl_for_CharityApi::CharityApi;
+
+	new_test_ext().execute_with(|| {
+		assert_eq!(
+			<TestRuntime as CharityApi<Block, u128, u64>>::pot_balance(),
+			Balances::minimum_balance()
+		);
+
+		assert_ok!(Charity::donate(Origin::signed(1), 10, vec![], None));
+		assert_eq!(
+			<TestRuntime as CharityApi<Block, u128, u64>>::pot_balance(),
+			11
+		);
+	})
+}
+
+#[test]
+fn runtime_api_reports_pot_account() {
+	use charity_runtime_api::runtime_decl_for_CharityApi::CharityApi;
+
+	new_test_ext().execute_with(|| {
+		assert_eq!(
+			<TestRuntime as CharityApi<Block, u128, u64>>::pot_account(),
+			Charity::account_id()
+		);
+	})
+}
+
+#[test]
+fn donations_and_allocations_fail_while_paused() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Charity::donate(Origin::signed(1), 10, vec![], None));
+
+		assert_ok!(Charity::pause(RawOrigin::Root.into()));
+		assert!(Charity::is_paused());
+		assert_noop!(
+			Charity::donate(Origin::signed(1), 2, vec![], None),
+			Error::<TestRuntime, DefaultInstance>::PalletPaused
+		);
+		assert_noop!(
+			Charity::allocate(RawOrigin::Root.into(), 2, 5),
+			Error::<TestRuntime, DefaultInstance>::PalletPaused
+		);
+		assert_noop!(
+			Charity::allocate_proportional(
+				RawOrigin::Root.into(),
+				vec![(2, Perbill::from_percent(10))],
+				RemainderPolicy::KeepInPot
+			),
+			Error::<TestRuntime, DefaultInstance>::PalletPaused
+		);
+		assert_eq!(Charity::pot(), 11);
+
+		assert_ok!(Charity::unpause(RawOrigin::Root.into()));
+		assert!(!Charity::is_paused());
+		assert_ok!(Charity::donate(Origin::signed(1), 2, vec![], None));
+		assert_ok!(Charity::allocate(RawOrigin::Root.into(), 2, 5));
+		assert_eq!(Charity::pot(), 8);
+
+		let our_events = System::events()
+			.into_iter()
+			.filter_map(|r| match r.event {
+				Event::charity(e @ RawEvent::Paused) | Event::charity(e @ RawEvent::Unpaused) => {
+					Some(e)
+				}
+				_ => None,
+			})
+			.collect::<Vec<_>>();
+		assert_eq!(our_events, vec![RawEvent::Paused, RawEvent::Unpaused]);
+	})
+}
+
+#[test]
+fn only_root_can_pause() {
+	new_test_ext().execute_with(|| {
+		assert!(Charity::pause(Origin::signed(1)).is_err());
+		assert!(!Charity::is_paused());
+	})
+}
+
+/// Run `on_initialize` for every block up to and including `n`
+fn run_to_block(n: u64) {
+	while System::block_number() < n {
+		System::set_block_number(System::block_number() + 1);
+		MockScheduler::on_initialize(System::block_number());
+		Charity::on_initialize(System::block_number());
+	}
+}
+
+#[test]
+fn scheduled_allocations_run_at_their_block() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		assert_ok!(Charity::donate(Origin::signed(1), 10, vec![], None));
+		assert_ok!(Charity::schedule_allocation(
+			RawOrigin::Root.into(),
+			3,
+			6,
+			3
+		));
+		assert_eq!(
+			System::events().last().unwrap().event,
+			Event::charity(RawEvent::AllocationScheduled(3, 6, 3))
+		);
+
+		run_to_block(2);
+		assert_eq!(Balances::free_balance(3), 1);
+		assert_eq!(Charity::pot(), 11);
+
+		run_to_block(3);
+		assert_eq!(Balances::free_balance(3), 1 + 6);
+		assert_eq!(Charity::pot(), 5);
+		assert_eq!(Charity::total_allocated(), 6);
+
+		// It only runs once
+		run_to_block(5);
+		assert_eq!(Balances::free_balance(3), 1 + 6);
+	})
+}
+
+#[test]
+fn allocations_cannot_be_scheduled_in_the_past() {
+	new_test_ext().execute_with(|| {
+		run_to_block(4);
+		assert_noop!(
+			Charity::schedule_allocation(RawOrigin::Root.into(), 3, 6, 4),
+			Error::<TestRuntime, DefaultInstance>::ScheduledInThePast
+		);
+		assert_noop!(
+			Charity::schedule_allocation(Origin::signed(1), 3, 6, 5),
+			sp_runtime::DispatchError::BadOrigin
+		);
+		assert!(ScheduledCalls::get().is_empty());
+	})
+}
+
+#[test]
+fn recurring_donations_follow_their_interval() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Charity::schedule_donation(Origin::signed(1), 2, 3));
+		assert_eq!(Charity::recurring_donation_of(1), Some((2, 3)));
+
+		run_to_block(2);
+		assert_eq!(Charity::pot(), 1);
+		run_to_block(3);
+		assert_eq!(Charity::pot(), 3);
+		run_to_block(5);
+		assert_eq!(Charity::pot(), 3);
+		run_to_block(6);
+		assert_eq!(Charity::pot(), 5);
+
+		assert_eq!(Charity::donation_of(1), 4);
+		assert_eq!(Charity::total_donated(), 4);
+	})
+}
+
+#[test]
+fn cancelled_recurring_donation_stops() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Charity::schedule_donation(Origin::signed(1), 2, 2));
+		run_to_block(2);
+		assert_eq!(Charity::pot(), 3);
+
+		assert_ok!(Charity::cancel_recurring_donation(Origin::signed(1)));
+		assert_eq!(Charity::recurring_donation_of(1), None);
+		run_to_block(6);
+		assert_eq!(Charity::pot(), 3);
+
+		assert_noop!(
+			Charity::cancel_recurring_donation(Origin::signed(1)),
+			Error::<TestRuntime, DefaultInstance>::NoRecurringDonation
+		);
+	})
+}
+
+#[test]
+fn recurring_donation_skipped_when_donor_is_short() {
+	new_test_ext().execute_with(|| {
+		// User 3 only has 1 token
+		assert_ok!(Charity::schedule_donation(Origin::signed(3), 2, 2));
+		run_to_block(2);
+
+		assert_eq!(Charity::pot(), 1);
+		assert_eq!(
+			System::events().last().unwrap().event,
+			Event::charity(RawEvent::RecurringDonationSkipped(3, 2))
+		);
+		// The schedule is kept for the next instalment
+		assert_eq!(Charity::recurring_donation_of(3), Some((2, 2)));
+	})
+}
+
+#[test]
+fn recurring_donations_hold_a_deposit_and_are_capped() {
+	new_test_ext().execute_with(|| {
+		RecurringDonationDeposit::set(3);
+		assert_ok!(Charity::schedule_donation(Origin::signed(1), 2, 3));
+		assert_eq!(Balances::reserved_balance(1), 3);
+		// Replacing a schedule neither takes a second deposit nor a second slot
+		assert_ok!(Charity::schedule_donation(Origin::signed(1), 4, 3));
+		assert_eq!(Balances::reserved_balance(1), 3);
+		assert_eq!(Charity::recurring_donation_count(), 1);
+
+		// A donor who can't put up the deposit can't schedule
+		assert_noop!(
+			Charity::schedule_donation(Origin::signed(3), 2, 3),
+			Error::<TestRuntime, DefaultInstance>::InsufficientFunds
+		);
+		assert_ok!(Charity::schedule_donation(Origin::signed(2), 2, 3));
+		assert_noop!(
+			Charity::schedule_donation(Origin::signed(5), 2, 3),
+			Error::<TestRuntime, DefaultInstance>::TooManyRecurringDonations
+		);
+
+		// Cancelling returns the deposit and frees the slot
+		assert_ok!(Charity::cancel_recurring_donation(Origin::signed(1)));
+		assert_eq!(Balances::reserved_balance(1), 0);
+		assert_eq!(Balances::free_balance(1), 13);
+		assert_ok!(Charity::schedule_donation(Origin::signed(5), 2, 3));
+		assert_eq!(Charity::recurring_donation_count(), 2);
+	})
+}
+
+#[test]
+fn recurring_donation_must_be_valid() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(
+			Charity::schedule_donation(Origin::signed(1), 2, 0),
+			Error::<TestRuntime, DefaultInstance>::ZeroInterval
+		);
+		assert_noop!(
+			Charity::schedule_donation(Origin::signed(1), 1, 5),
+			Error::<TestRuntime, DefaultInstance>::DonationTooSmall
+		);
+	})
+}
+
+#[test]
+fn reserved_pot_accounting_stays_consistent() {
+	new_test_ext().execute_with(|| {
+		UseReserved::set(true);
+		let pot_account = Charity::account_id();
+		assert_eq!(Charity::pot(), 0);
+
+		// Donations are locked away in the pot account's reserved balance
+		assert_ok!(Charity::donate(Origin::signed(1), 10, vec![], None));
+		assert_eq!(Charity::pot(), 10);
+		assert_eq!(Balances::reserved_balance(pot_account), 10);
+		assert_eq!(Balances::free_balance(pot_account), 1);
+
+		// Allocations unreserve exactly what they pay out
+		assert_ok!(Charity::allocate(RawOrigin::Root.into(), 2, 4));
+		assert_eq!(Charity::pot(), 6);
+		assert_eq!(Balances::reserved_balance(pot_account), 6);
+		assert_eq!(Balances::free_balance(pot_account), 1);
+		assert_eq!(Balances::free_balance(2), 15);
+
+		// Absorbed imbalances are reserved too
+		Charity::on_nonzero_unbalanced(pallet_balances::NegativeImbalance::new(5));
+		assert_eq!(Charity::pot(), 11);
+		assert_eq!(Balances::free_balance(pot_account), 1);
+
+		// The whole reserved pot can be withdrawn without touching the existential deposit
+		assert_ok!(Charity::withdraw_donation(Origin::signed(1), 10));
+		assert_eq!(Charity::pot(), 1);
+		assert_eq!(Balances::reserved_balance(pot_account), 1);
+		assert_eq!(Balances::free_balance(pot_account), 1);
+	})
+}
+
+#[test]
+fn reserved_pot_cant_overspend() {
+	new_test_ext().execute_with(|| {
+		UseReserved::set(true);
+		assert_ok!(Charity::donate(Origin::signed(1), 10, vec![], None));
+
+		// The free existential deposit is not part of the reserved pot
+		assert_err!(
+			Charity::allocate(RawOrigin::Root.into(), 2, 11),
+			Error::<TestRuntime, DefaultInstance>::InsufficientPot
+		);
+		assert_eq!(Balances::reserved_balance(Charity::account_id()), 10);
+	})
+}
+
+#[test]
+fn donation_beyond_cap_overflows_to_sink() {
+	new_test_ext().execute_with(|| {
+		MaxPotBalance::set(20);
+		assert_ok!(Charity::donate(Origin::signed(5), 15, vec![], None));
+		assert_eq!(Charity::pot(), 16);
+
+		// Only 4 more fit in the pot; the other 6 go to the overflow account
+		assert_ok!(Charity::donate(Origin::signed(1), 10, vec![], None));
+		assert_eq!(Charity::pot(), 20);
+		assert_eq!(Balances::free_balance(OverflowDestination::get()), 6);
+		assert_eq!(Balances::free_balance(1), 3);
+		assert_eq!(Charity::donation_of(1), 4);
+		assert_eq!(Charity::total_donated(), 19);
+
+		let our_events = System::events()
+			.into_iter()
+			.filter_map(|r| match r.event {
+				Event::charity(e) => Some(e),
+				_ => None,
+			})
+			.collect::<Vec<_>>();
+		assert_eq!(
+			our_events[3..],
+			[
+				RawEvent::DonationReceived(1, 10, 4, 20, Default::default(), 2, 4, 1),
+				RawEvent::RankChanged(1, None, Some(2)),
+				RawEvent::DonationOverflowed(1, 6),
+			]
+		);
+	})
+}
+
+#[test]
+fn donation_fully_matched() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Charity::fund_matching(Origin::signed(5), 10));
+		assert_eq!(Charity::matching_pool(), 10);
+
+		assert_ok!(Charity::donate(Origin::signed(1), 4, vec![], None));
+		assert_eq!(Charity::pot(), 1 + 4 + 4);
+		assert_eq!(Charity::matching_pool(), 6);
+		// Only what the donor gave is theirs to withdraw
+		assert_eq!(Charity::donation_of(1), 4);
+		assert!(System::events()
+			.iter()
+			.any(|r| r.event == Event::charity(RawEvent::DonationMatched(1, 4))));
+	})
+}
+
+#[test]
+fn donation_partially_matched_when_pool_runs_low() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Charity::fund_matching(Origin::signed(5), 3));
+
+		assert_ok!(Charity::donate(Origin::signed(1), 10, vec![], None));
+		assert_eq!(Charity::pot(), 1 + 10 + 3);
+		assert_eq!(Charity::matching_pool(), 0);
+		// The matching account keeps its existential deposit
+		assert_eq!(
+			Balances::free_balance(Charity::matching_account_id()),
+			Balances::minimum_balance()
+		);
+		assert!(System::events()
+			.iter()
+			.any(|r| r.event == Event::charity(RawEvent::DonationMatched(1, 3))));
+	})
+}
+
+#[test]
+fn donation_unmatched_when_pool_empty() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Charity::donate(Origin::signed(1), 10, vec![], None));
+		assert_eq!(Charity::pot(), 1 + 10);
+		assert!(!System::events()
+			.iter()
+			.any(|r| matches!(r.event, Event::charity(RawEvent::DonationMatched(..)))));
+	})
+}
+
+#[test]
+fn failed_overflow_rolls_back_pot_deposit() {
+	new_test_ext().execute_with(|| {
+		// The first 5 fit in the pot and are moved before the overflow transfer is attempted. User
+		// 1 cannot cover the remaining 15, so the whole donation must be undone, not just its tail.
+		MaxPotBalance::set(6);
+		assert_noop!(
+			Charity::donate(Origin::signed(1), 20, vec![], None),
+			Error::<TestRuntime, DefaultInstance>::InsufficientFunds
+		);
+		assert_eq!(Balances::free_balance(1), 13);
+		assert_eq!(Charity::donation_of(1), 0);
+	})
+}
+
+#[test]
+fn causes_keep_separate_tallies() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Charity::donate_to_cause(
+			Origin::signed(1),
+			b"education".to_vec(),
+			10
+		));
+		assert_ok!(Charity::donate_to_cause(
+			Origin::signed(5),
+			b"disaster relief".to_vec(),
+			15
+		));
+		assert_eq!(Charity::cause_balance(b"education".to_vec()), 10);
+		assert_eq!(Charity::cause_balance(b"disaster relief".to_vec()), 15);
+		assert_eq!(Charity::pot(), 1 + 10 + 15);
+
+		assert_ok!(Charity::allocate_from_cause(
+			RawOrigin::Root.into(),
+			b"education".to_vec(),
+			2,
+			4
+		));
+		assert_eq!(Balances::free_balance(2), 11 + 4);
+		assert_eq!(Charity::cause_balance(b"education".to_vec()), 6);
+		assert_eq!(Charity::cause_balance(b"disaster relief".to_vec()), 15);
+		assert_eq!(
+			System::events().last().unwrap().event,
+			Event::charity(RawEvent::FundsAllocatedFromCause(
+				b"education".to_vec().try_into().unwrap(),
+				2,
+				4,
+				6
+			))
+		);
+
+		// The education tally can't be topped up from disaster relief's share
+		assert_noop!(
+			Charity::allocate_from_cause(RawOrigin::Root.into(), b"education".to_vec(), 2, 7),
+			Error::<TestRuntime, DefaultInstance>::InsufficientCauseFunds
+		);
+	})
+}
+
+#[test]
+fn cause_name_length_is_enforced() {
+	new_test_ext().execute_with(|| {
+		let cause = vec![b'c'; MaxCauseNameLength::get() as usize + 1];
+		assert_noop!(
+			Charity::donate_to_cause(Origin::signed(1), cause, 5),
+			Error::<TestRuntime, DefaultInstance>::CauseNameTooLong
+		);
+	})
+}
+
+#[test]
+fn cause_name_of_the_maximum_length_is_accepted() {
+	new_test_ext().execute_with(|| {
+		let longest = vec![b'c'; MaxCauseNameLength::get() as usize];
+		let too_long = vec![b'c'; MaxCauseNameLength::get() as usize + 1];
+		assert_ok!(Charity::donate_to_cause(
+			Origin::signed(1),
+			longest.clone(),
+			5
+		));
+		assert_eq!(Charity::cause_list(), vec![longest.clone()]);
+
+		// Every call naming a cause applies the same bound
+		assert_noop!(
+			Charity::transfer_between_causes(RawOrigin::Root.into(), longest, too_long.clone(), 1),
+			Error::<TestRuntime, DefaultInstance>::CauseNameTooLong
+		);
+		assert_noop!(
+			Charity::donate_many(Origin::signed(1), vec![(too_long.clone(), 1)]),
+			Error::<TestRuntime, DefaultInstance>::CauseNameTooLong
+		);
+		assert_noop!(
+			Charity::close_cause(RawOrigin::Root.into(), too_long),
+			Error::<TestRuntime, DefaultInstance>::CauseNameTooLong
+		);
+	})
+}
+
+#[test]
+fn bounded_vec_refuses_to_decode_past_its_bound() {
+	let longest = vec![b'c'; MaxCauseNameLength::get() as usize];
+	let too_long = vec![b'c'; MaxCauseNameLength::get() as usize + 1];
+
+	// The encoding is the plain `Vec` one, so existing storage decodes unchanged
+	let decoded = BoundedVec::<u8, MaxCauseNameLength>::decode(&mut &longest.encode()[..]);
+	assert_eq!(decoded.unwrap(), longest);
+	assert!(BoundedVec::<u8, MaxCauseNameLength>::decode(&mut &too_long.encode()[..]).is_err());
+}
+
+#[test]
+fn genesis_donations_are_recorded() {
+	ExtBuilder::default()
+		.with_donations(vec![(1, 10), (2, 5)])
+		.build()
+		.execute_with(|| {
+			assert_eq!(Charity::pot(), Balances::minimum_balance() + 15);
+			assert_eq!(Charity::donation_of(1), 10);
+			assert_eq!(Charity::donation_of(2), 5);
+			assert_eq!(Charity::total_donated(), 15);
+			assert_eq!(Charity::distinct_donors(), 2);
+			assert_eq!(Balances::free_balance(1), 3);
+			assert_eq!(Balances::free_balance(2), 6);
+		})
+}
+
+#[test]
+fn genesis_endowment_seeds_the_pot() {
+	ExtBuilder::default()
+		.with_endowment(50)
+		.build()
+		.execute_with(|| {
+			assert_eq!(Charity::pot(), 50);
+			assert_eq!(Charity::total_donated(), 50);
+			assert_eq!(Charity::donation_of(1), 0);
+
+			// Donations stack on top of the endowment
+			assert_ok!(Charity::donate(Origin::signed(1), 10, vec![], None));
+			assert_eq!(Charity::pot(), 60);
+			assert_eq!(Charity::total_donated(), 60);
+			assert_eq!(Charity::donation_of(1), 10);
+		})
+}
+
+#[test]
+fn genesis_endowment_below_the_deposit_still_creates_the_pot() {
+	ExtBuilder::default()
+		.with_endowment(0)
+		.with_donations(vec![(1, 10)])
+		.build()
+		.execute_with(|| {
+			assert_eq!(Charity::pot(), Balances::minimum_balance() + 10);
+			assert_eq!(Charity::total_donated(), 10);
+		})
+}
+
+#[test]
+fn donation_count_increments() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Charity::donate(Origin::signed(1), 2, vec![], None));
+		assert_ok!(Charity::donate(Origin::signed(2), 3, vec![], None));
+		assert_ok!(Charity::donate(Origin::signed(1), 4, vec![], None));
+		assert_eq!(Charity::donation_count(), 3);
+		assert!(System::events().iter().any(|r| r.event
+			== Event::charity(RawEvent::DonationReceived(
+				1,
+				4,
+				4,
+				10,
+				Default::default(),
+				3,
+				6,
+				2
+			))));
+	})
+}
+
+#[test]
+fn distinct_donors_counts_each_donor_once() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Charity::donate(Origin::signed(1), 2, vec![], None));
+		assert_ok!(Charity::donate(Origin::signed(2), 3, vec![], None));
+		assert_eq!(Charity::distinct_donors(), 2);
+
+		// A repeat donation is not a new donor
+		assert_ok!(Charity::donate(Origin::signed(1), 4, vec![], None));
+		assert_eq!(Charity::distinct_donors(), 2);
+
+		assert_ok!(Charity::donate(Origin::signed(5), 2, vec![], None));
+		assert_eq!(Charity::distinct_donors(), 3);
+	})
+}
+
+#[test]
+fn donors_who_withdraw_everything_stop_counting() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Charity::donate(Origin::signed(1), 6, vec![], None));
+		assert_ok!(Charity::withdraw_donation(Origin::signed(1), 2));
+		assert_eq!(Charity::distinct_donors(), 1);
+
+		assert_ok!(Charity::withdraw_donation(Origin::signed(1), 4));
+		assert_eq!(Charity::distinct_donors(), 0);
+
+		// Coming back makes them a donor again
+		assert_ok!(Charity::donate(Origin::signed(1), 2, vec![], None));
+		assert_eq!(Charity::distinct_donors(), 1);
+	})
+}
+
+#[test]
+fn migrate_pot_moves_funds_to_new_account() {
+	new_test_ext().execute_with(|| {
+		let old: u128 = Charity::account_id();
+		assert_ok!(Charity::donate(Origin::signed(1), 10, vec![], None));
+
+		assert_ok!(Charity::migrate_pot(RawOrigin::Root.into(), *b"Giving!!"));
+		let new: u128 = ModuleId(*b"Giving!!").into_account();
+		assert_eq!(Charity::account_id(), new);
+		assert_eq!(Balances::free_balance(new), 10);
+		assert_eq!(Balances::free_balance(old), Balances::minimum_balance());
+		assert_eq!(Charity::pot(), 10);
+		assert_eq!(
+			System::events().last().unwrap().event,
+			Event::charity(RawEvent::PotMigrated(old, new, 10))
+		);
+
+		// Later donations land in the new account
+		assert_ok!(Charity::donate(Origin::signed(2), 5, vec![], None));
+		assert_eq!(Balances::free_balance(new), 15);
+
+		assert_noop!(
+			Charity::migrate_pot(RawOrigin::Root.into(), *b"Giving!!"),
+			Error::<TestRuntime, DefaultInstance>::SamePotId
+		);
+	})
+}
+
+#[test]
+fn reserved_pot_keeps_paying_out_after_migrating() {
+	new_test_ext().execute_with(|| {
+		UseReserved::set(true);
+		assert_ok!(Charity::donate(Origin::signed(1), 10, vec![], None));
+
+		assert_ok!(Charity::migrate_pot(RawOrigin::Root.into(), *b"Giving!!"));
+		let new: u128 = ModuleId(*b"Giving!!").into_account();
+		assert_eq!(Balances::free_balance(new), Balances::minimum_balance());
+		assert_eq!(Balances::reserved_balance(new), 9);
+		assert_eq!(Charity::pot(), 9);
+
+		assert_ok!(Charity::withdraw_donation(Origin::signed(1), 3));
+		assert_eq!(Balances::free_balance(1), 13 - 10 + 3);
+		assert_ok!(Charity::refund_all(RawOrigin::Root.into(), 1));
+		assert_eq!(Balances::free_balance(1), 13 - 10 + 3 + 6);
+		assert_eq!(Charity::pot(), 0);
+		assert_eq!(Balances::free_balance(new), Balances::minimum_balance());
+	})
+}
+
+#[test]
+fn locked_funds_are_excluded_from_pot() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Charity::donate(Origin::signed(1), 10, vec![], None));
+		assert_ok!(Charity::lock_for_proposal(6));
+		assert_eq!(Charity::pot(), 11 - 6);
+		assert_err!(
+			Charity::allocate(RawOrigin::Root.into(), 2, 6),
+			Error::<TestRuntime, DefaultInstance>::InsufficientPot
+		);
+		assert_noop!(
+			Charity::lock_for_proposal(5),
+			Error::<TestRuntime, DefaultInstance>::InsufficientPot
+		);
+
+		assert_ok!(Charity::release_lock(6));
+		assert_eq!(Charity::pot(), 11);
+		assert_ok!(Charity::allocate(RawOrigin::Root.into(), 2, 6));
+		assert_noop!(
+			Charity::release_lock(1),
+			Error::<TestRuntime, DefaultInstance>::InsufficientLocked
+		);
+	})
+}
+
+#[test]
+fn locked_funds_are_excluded_from_reserved_pot() {
+	new_test_ext().execute_with(|| {
+		UseReserved::set(true);
+		assert_ok!(Charity::donate(Origin::signed(1), 10, vec![], None));
+		assert_ok!(Charity::lock_for_proposal(6));
+		assert_eq!(Charity::pot(), 4);
+		assert_err!(
+			Charity::allocate(RawOrigin::Root.into(), 2, 5),
+			Error::<TestRuntime, DefaultInstance>::InsufficientPot
+		);
+
+		assert_ok!(Charity::release_lock(6));
+		assert_eq!(Charity::pot(), 10);
+		assert_eq!(Balances::reserved_balance(Charity::account_id()), 10);
+	})
+}
+
+#[test]
+fn vesting_grant_releases_linearly() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Charity::donate(Origin::signed(5), 15, vec![], None));
+		assert_ok!(Charity::allocate_with_vesting(
+			RawOrigin::Root.into(),
+			2,
+			9,
+			3,
+			3
+		));
+		// The whole grant is held back from the pot straight away
+		assert_eq!(Charity::pot(), 16 - 9);
+
+		run_to_block(2);
+		assert_eq!(Balances::free_balance(2), 11);
+		run_to_block(3);
+		assert_eq!(Balances::free_balance(2), 14);
+		run_to_block(4);
+		assert_eq!(Balances::free_balance(2), 17);
+		run_to_block(6);
+		assert_eq!(Balances::free_balance(2), 20);
+		assert_eq!(Charity::vesting_schedule_of(2), None);
+		assert_eq!(Charity::locked_funds(), 0);
+		assert_eq!(Charity::pot(), 7);
+	})
+}
+
+#[test]
+fn vesting_grant_pays_final_partial_chunk() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Charity::donate(Origin::signed(5), 15, vec![], None));
+		assert_ok!(Charity::allocate_with_vesting(
+			RawOrigin::Root.into(),
+			2,
+			7,
+			3,
+			2
+		));
+
+		run_to_block(4);
+		assert_eq!(Balances::free_balance(2), 11 + 7);
+		assert_eq!(Charity::vesting_schedule_of(2), None);
+
+		let releases = System::events()
+			.into_iter()
+			.filter_map(|r| match r.event {
+				Event::charity(RawEvent::VestedChunkReleased(dest, amount, remaining)) => {
+					Some((dest, amount, remaining))
+				}
+				_ => None,
+			})
+			.collect::<Vec<_>>();
+		assert_eq!(releases, vec![(2, 3, 4), (2, 3, 1), (2, 1, 0)]);
+	})
+}
+
+#[test]
+fn pending_vesting_shrinks_as_the_grant_is_released() {
+	use charity_runtime_api::runtime_decl_for_CharityApi::CharityApi;
+
+	new_test_ext().execute_with(|| {
+		assert_ok!(Charity::donate(Origin::signed(5), 15, vec![], None));
+		assert_eq!(Charity::pending_vesting(&2), 0);
+		assert_ok!(Charity::allocate_with_vesting(
+			RawOrigin::Root.into(),
+			2,
+			7,
+			3,
+			2
+		));
+		assert_eq!(Charity::pending_vesting(&2), 7);
+
+		run_to_block(2);
+		assert_eq!(Charity::pending_vesting(&2), 4);
+		assert_eq!(
+			<TestRuntime as CharityApi<Block, u128, u64>>::pending_vesting(2),
+			4
+		);
+		run_to_block(3);
+		assert_eq!(Charity::pending_vesting(&2), 1);
+		run_to_block(4);
+		assert_eq!(Charity::pending_vesting(&2), 0);
+	})
+}
+
+#[test]
+fn stats_match_the_individual_accessors() {
+	use charity_runtime_api::runtime_decl_for_CharityApi::CharityApi;
+
+	new_test_ext().execute_with(|| {
+		assert_eq!(
+			Charity::stats(),
+			CharityStats {
+				pot: 1,
+				..Default::default()
+			}
+		);
+
+		assert_ok!(Charity::donate(Origin::signed(5), 15, vec![], None));
+		assert_ok!(Charity::donate(Origin::signed(1), 10, vec![], None));
+		assert_ok!(Charity::donate(Origin::signed(1), 2, vec![], None));
+		assert_ok!(Charity::allocate(RawOrigin::Root.into(), 3, 12));
+		assert_ok!(Charity::allocate(RawOrigin::Root.into(), 4, 5));
+
+		let stats = Charity::stats();
+		assert_eq!(stats.pot, Charity::pot());
+		assert_eq!(stats.total_donated, Charity::total_donated());
+		assert_eq!(stats.total_allocated, Charity::total_allocated());
+		assert_eq!(stats.distinct_donors, Charity::distinct_donors());
+		assert_eq!(stats.donation_count, Charity::donation_count());
+		assert_eq!(
+			stats,
+			CharityStats {
+				pot: 11,
+				total_donated: 27,
+				total_allocated: 17,
+				distinct_donors: 2,
+				donation_count: 3,
+			}
+		);
+		assert_eq!(
+			<TestRuntime as CharityApi<Block, u128, u64>>::stats(),
+			stats
+		);
+	})
+}
+
+#[test]
+fn cancelled_vesting_grants_stop_releasing() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Charity::donate(Origin::signed(5), 15, vec![], None));
+		assert_ok!(Charity::allocate_with_vesting(
+			RawOrigin::Root.into(),
+			2,
+			9,
+			3,
+			2
+		));
+		run_to_block(3);
+		assert_eq!(Balances::free_balance(2), 11 + 6);
+
+		assert_ok!(Charity::cancel_vesting(RawOrigin::Root.into(), 2));
+		assert!(System::events()
+			.iter()
+			.any(|r| r.event == Event::charity(RawEvent::VestingCancelled(2, 3))));
+		assert_eq!(Charity::vesting_schedule_of(2), None);
+		assert_eq!(Charity::locked_funds(), 0);
+
+		// Nothing more is paid, and the unreleased 3 are back in the pot
+		run_to_block(6);
+		assert_eq!(Balances::free_balance(2), 11 + 6);
+		assert_eq!(Charity::pot(), 16 - 6);
+	})
+}
+
+#[test]
+fn only_root_may_cancel_an_existing_vesting_grant() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(
+			Charity::cancel_vesting(RawOrigin::Root.into(), 2),
+			Error::<TestRuntime, DefaultInstance>::NoSuchVestingSchedule
+		);
+
+		assert_ok!(Charity::donate(Origin::signed(5), 15, vec![], None));
+		assert_ok!(Charity::allocate_with_vesting(
+			RawOrigin::Root.into(),
+			2,
+			9,
+			3,
+			2
+		));
+		assert_noop!(
+			Charity::cancel_vesting(Origin::signed(1), 2),
+			sp_runtime::DispatchError::BadOrigin
+		);
+	})
+}
+
+#[test]
+fn vesting_grant_must_be_valid() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Charity::donate(Origin::signed(5), 15, vec![], None));
+		assert_noop!(
+			Charity::allocate_with_vesting(RawOrigin::Root.into(), 2, 9, 0, 3),
+			Error::<TestRuntime, DefaultInstance>::InvalidVestingSchedule
+		);
+		assert_noop!(
+			Charity::allocate_with_vesting(RawOrigin::Root.into(), 2, 16, 1, 3),
+			Error::<TestRuntime, DefaultInstance>::InsufficientPot
+		);
+		assert_ok!(Charity::allocate_with_vesting(
+			RawOrigin::Root.into(),
+			2,
+			4,
+			1,
+			3
+		));
+		assert_noop!(
+			Charity::allocate_with_vesting(RawOrigin::Root.into(), 2, 4, 1, 3),
+			Error::<TestRuntime, DefaultInstance>::VestingScheduleExists
+		);
+	})
+}
+
+#[test]
+fn donate_many_credits_each_cause() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Charity::donate_many(
+			Origin::signed(5),
+			vec![
+				(b"education".to_vec(), 4),
+				(b"relief".to_vec(), 6),
+				(b"education".to_vec(), 1),
+			]
+		));
+		assert_eq!(Balances::free_balance(5), 19 - 11);
+		assert_eq!(Charity::pot(), 1 + 11);
+		assert_eq!(Charity::donation_of(5), 11);
+		assert_eq!(Charity::cause_balance(b"education".to_vec()), 5);
+		assert_eq!(Charity::cause_balance(b"relief".to_vec()), 6);
+		assert_eq!(
+			Charity::causes(),
+			vec![(b"education".to_vec(), 5), (b"relief".to_vec(), 6)]
+		);
+	})
+}
+
+#[test]
+fn causes_are_registered_once() {
+	use charity_runtime_api::runtime_decl_for_CharityApi::CharityApi;
+
+	new_test_ext().execute_with(|| {
+		assert!(Charity::causes().is_empty());
+
+		assert_ok!(Charity::donate_to_cause(
+			Origin::signed(1),
+			b"relief".to_vec(),
+			3
+		));
+		assert_eq!(Charity::cause_list(), vec![b"relief".to_vec()]);
+
+		assert_ok!(Charity::donate_to_cause(
+			Origin::signed(2),
+			b"relief".to_vec(),
+			4
+		));
+		assert_eq!(Charity::cause_list(), vec![b"relief".to_vec()]);
+		assert_eq!(
+			<TestRuntime as CharityApi<Block, u128, u64>>::causes(),
+			vec![(b"relief".to_vec(), 7)]
+		);
+	})
+}
+
+#[test]
+fn funds_can_be_moved_between_causes() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Charity::donate_to_cause(
+			Origin::signed(5),
+			b"relief".to_vec(),
+			10
+		));
+
+		assert_ok!(Charity::transfer_between_causes(
+			RawOrigin::Root.into(),
+			b"relief".to_vec(),
+			b"education".to_vec(),
+			4
+		));
+		assert_eq!(Charity::cause_balance(b"relief".to_vec()), 6);
+		assert_eq!(Charity::cause_balance(b"education".to_vec()), 4);
+		assert_eq!(
+			Charity::cause_list(),
+			vec![b"relief".to_vec(), b"education".to_vec()]
+		);
+		// The tokens themselves stay in the pot
+		assert_eq!(Charity::pot(), 1 + 10);
+		assert!(System::events().iter().any(|r| r.event
+			== Event::charity(RawEvent::CauseFundsTransferred(
+				b"relief".to_vec().try_into().unwrap(),
+				b"education".to_vec().try_into().unwrap(),
+				4
+			))));
+	})
+}
+
+#[test]
+fn cant_move_more_than_a_cause_holds() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Charity::donate_to_cause(
+			Origin::signed(5),
+			b"relief".to_vec(),
+			10
+		));
+
+		assert_noop!(
+			Charity::transfer_between_causes(
+				RawOrigin::Root.into(),
+				b"relief".to_vec(),
+				b"education".to_vec(),
+				11
+			),
+			Error::<TestRuntime, DefaultInstance>::InsufficientCauseFunds
+		);
+		assert_eq!(Charity::cause_balance(b"relief".to_vec()), 10);
+	})
+}
+
+#[test]
+fn closing_a_cause_returns_its_funds_to_the_general_pot() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Charity::donate_to_cause(
+			Origin::signed(5),
+			b"relief".to_vec(),
+			6
+		));
+		assert_ok!(Charity::donate_to_cause(
+			Origin::signed(5),
+			b"education".to_vec(),
+			4
+		));
+
+		assert_ok!(Charity::close_cause(
+			RawOrigin::Root.into(),
+			b"relief".to_vec()
+		));
+
+		assert_eq!(Charity::cause_balance(b"relief".to_vec()), 0);
+		assert_eq!(Charity::cause_list(), vec![b"education".to_vec()]);
+		assert_eq!(Charity::pot(), 1 + 10);
+		assert!(System::events().iter().any(|r| r.event
+			== Event::charity(RawEvent::CauseClosed(
+				b"relief".to_vec().try_into().unwrap(),
+				6
+			))));
+
+		assert_noop!(
+			Charity::close_cause(RawOrigin::Root.into(), b"relief".to_vec()),
+			Error::<TestRuntime, DefaultInstance>::UnknownCause
+		);
+	})
+}
+
+#[test]
+fn cause_list_is_bounded() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Charity::donate_many(
+			Origin::signed(5),
+			vec![
+				(b"a".to_vec(), 2),
+				(b"b".to_vec(), 2),
+				(b"c".to_vec(), 2),
+				(b"d".to_vec(), 2),
+			]
+		));
+
+		assert_noop!(
+			Charity::donate_to_cause(Origin::signed(5), b"e".to_vec(), 2),
+			Error::<TestRuntime, DefaultInstance>::TooManyCauses
+		);
+		// Known causes can still be given to
+		assert_ok!(Charity::donate_to_cause(
+			Origin::signed(5),
+			b"a".to_vec(),
+			2
+		));
+		assert_eq!(Charity::causes().len(), 4);
+	})
+}
+
+#[test]
+fn closing_a_cause_makes_room_for_a_new_one() {
+	new_test_ext().execute_with(|| {
+		for cause in [b"a", b"b", b"c", b"d"].iter() {
+			assert_ok!(Charity::donate_to_cause(
+				Origin::signed(5),
+				cause.to_vec(),
+				2
+			));
+		}
+		assert_noop!(
+			Charity::donate_to_cause(Origin::signed(5), b"e".to_vec(), 2),
+			Error::<TestRuntime, DefaultInstance>::TooManyCauses
+		);
+
+		assert_ok!(Charity::close_cause(RawOrigin::Root.into(), b"b".to_vec()));
+		assert_ok!(Charity::donate_to_cause(
+			Origin::signed(5),
+			b"e".to_vec(),
+			2
+		));
+		assert_eq!(
+			Charity::cause_list(),
+			vec![b"a".to_vec(), b"c".to_vec(), b"d".to_vec(), b"e".to_vec()]
+		);
+	})
+}
+
+#[test]
+fn donate_many_over_budget_changes_nothing() {
+	new_test_ext().execute_with(|| {
+		// User 1 has 13 tokens, but the batch adds up to 14
+		assert_noop!(
+			Charity::donate_many(
+				Origin::signed(1),
+				vec![(b"education".to_vec(), 7), (b"relief".to_vec(), 7)]
+			),
+			Error::<TestRuntime, DefaultInstance>::InsufficientFunds
+		);
+		assert_noop!(
+			Charity::donate_many(
+				Origin::signed(1),
+				vec![(b"education".to_vec(), 1); MaxBatchSize::get() as usize + 1]
+			),
+			Error::<TestRuntime, DefaultInstance>::BatchTooLarge
+		);
+	})
+}
+
+#[test]
+fn donation_handler_is_told_about_donations() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Charity::donate(Origin::signed(1), 10, vec![], None));
+		assert_ok!(Charity::donate_to_cause(
+			Origin::signed(2),
+			b"education".to_vec(),
+			3
+		));
+		assert_noop!(
+			Charity::donate(Origin::signed(3), 5, vec![], None),
+			Error::<TestRuntime, DefaultInstance>::InsufficientFunds
+		);
+		assert_eq!(DonationsSeen::get(), vec![(1, 10), (2, 3)]);
+	})
+}
+
+#[test]
+fn donations_are_checked_by_the_validator() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Charity::donate(Origin::signed(1), 3, vec![], None));
+		assert_ok!(Charity::donate_to_cause(
+			Origin::signed(2),
+			b"education".to_vec(),
+			4
+		));
+		assert_eq!(DonationsValidated::get(), vec![(1, 3), (2, 4)]);
+	})
+}
+
+#[test]
+fn donations_the_validator_rejects_fail() {
+	new_test_ext().execute_with(|| {
+		RejectOddDonations::set(true);
+		assert_noop!(
+			Charity::donate(Origin::signed(1), 3, vec![], None),
+			sp_runtime::DispatchError::Other("only even donations are accepted")
+		);
+		assert_ok!(Charity::donate(Origin::signed(1), 4, vec![], None));
+		assert_eq!(Charity::pot(), Balances::minimum_balance() + 4);
+	})
+}
+
+#[test]
+fn donation_event_carries_donor_total() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Charity::donate(Origin::signed(1), 3, vec![], None));
+		assert_ok!(Charity::donate(Origin::signed(1), 5, vec![], None));
+		assert!(System::events().iter().any(|r| r.event
+			== Event::charity(RawEvent::DonationReceived(
+				1,
+				5,
+				5,
+				9,
+				Default::default(),
+				2,
+				8,
+				1
+			))));
+	})
+}
+
+#[test]
+fn refund_all_is_weighed_by_the_donor_count() {
+	assert!(<() as WeightInfo>::refund_all(10) > <() as WeightInfo>::refund_all(1));
+}
+
+#[test]
+fn refund_all_returns_pot_pro_rata() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Charity::donate(Origin::signed(1), 6, vec![], None));
+		assert_ok!(Charity::donate(Origin::signed(2), 3, vec![], None));
+		assert_ok!(Charity::donate(Origin::signed(5), 9, vec![], None));
+		Charity::on_nonzero_unbalanced(pallet_balances::NegativeImbalance::new(9));
+		// 27 can be refunded: half to 5, a third to 1 and a sixth to 2
+		assert_eq!(Charity::pot(), 1 + 27);
+
+		assert_noop!(
+			Charity::refund_all(RawOrigin::Root.into(), 2),
+			Error::<TestRuntime, DefaultInstance>::TooManyDonors
+		);
+		assert_ok!(Charity::refund_all(RawOrigin::Root.into(), 3));
+		assert_eq!(Balances::free_balance(1), 13 - 6 + 9);
+		assert_eq!(Balances::free_balance(2), 11 - 3 + 4);
+		assert_eq!(Balances::free_balance(5), 19 - 9 + 13);
+		// The rounding dust stays behind
+		assert_eq!(Charity::pot(), 1 + 1);
+		assert_eq!(Charity::donation_of(1), 0);
+		assert_eq!(Charity::total_donated(), 0);
+		assert_eq!(Charity::distinct_donors(), 0);
+		assert_eq!(
+			System::events().last().unwrap().event,
+			Event::charity(RawEvent::CharityDissolved(26, 3))
+		);
+	})
+}
+
+#[test]
+fn overtaking_another_donor_reports_the_rank_change() {
+	new_test_ext().execute_with(|| {
+		let rank_changes = || {
+			System::events()
+				.into_iter()
+				.filter_map(|r| match r.event {
+					Event::charity(e @ RawEvent::RankChanged(..)) => Some(e),
+					_ => None,
+				})
+				.collect::<Vec<_>>()
+		};
+
+		// Joining the board counts as a change from no rank at all
+		assert_ok!(Charity::donate(Origin::signed(5), 5, vec![], None));
+		assert_ok!(Charity::donate(Origin::signed(1), 10, vec![], None));
+		assert_eq!(
+			rank_changes(),
+			vec![
+				RawEvent::RankChanged(5, None, Some(1)),
+				RawEvent::RankChanged(1, None, Some(1)),
+			]
+		);
+		assert_eq!(Charity::donation_rank(&5), Some(2));
+
+		// Donor 5 moves from second to first, past donor 1
+		System::reset_events();
+		assert_ok!(Charity::donate(Origin::signed(5), 6, vec![], None));
+		assert_eq!(
+			rank_changes(),
+			vec![RawEvent::RankChanged(5, Some(2), Some(1))]
+		);
+		assert_eq!(Charity::donation_rank(&1), Some(2));
+
+		// Topping up without overtaking anyone reports nothing
+		System::reset_events();
+		assert_ok!(Charity::donate(Origin::signed(5), 2, vec![], None));
+		assert!(rank_changes().is_empty());
+	})
+}
+
+#[test]
+fn largest_donation_is_tracked() {
+	new_test_ext().execute_with(|| {
+		assert_eq!(Charity::largest_donation(), (0, 0));
+
+		assert_ok!(Charity::donate(Origin::signed(1), 8, vec![], None));
+		assert_eq!(Charity::largest_donation(), (1, 8));
+		assert!(System::events()
+			.iter()
+			.any(|r| r.event == Event::charity(RawEvent::NewRecordDonation(1, 8))));
+
+		// A smaller donation, or one that only ties the record, leaves it alone
+		assert_ok!(Charity::donate(Origin::signed(2), 5, vec![], None));
+		assert_ok!(Charity::donate(Origin::signed(5), 8, vec![], None));
+		assert_eq!(Charity::largest_donation(), (1, 8));
+
+		assert_ok!(Charity::donate(Origin::signed(5), 9, vec![], None));
+		assert_eq!(Charity::largest_donation(), (5, 9));
+		assert_eq!(
+			System::events()
+				.iter()
+				.filter(|r| matches!(r.event, Event::charity(RawEvent::NewRecordDonation(..))))
+				.count(),
+			2
+		);
+	})
+}
+
+#[test]
+fn asset_donations_and_allocations_work() {
+	new_test_ext().execute_with(|| {
+		AssetBalances::set(vec![((7, 1), 50)].into_iter().collect());
+
+		assert_ok!(Charity::donate_asset(Origin::signed(1), 7, 30));
+		assert_eq!(Charity::asset_pot(7), 30);
+		assert_eq!(asset_balance(7, 1), 20);
+		assert_eq!(asset_balance(7, Charity::account_id()), 30);
+
+		assert_ok!(Charity::allocate_asset(RawOrigin::Root.into(), 7, 2, 12));
+		assert_eq!(Charity::asset_pot(7), 18);
+		assert_eq!(asset_balance(7, 2), 12);
+		assert_eq!(
+			System::events().last().unwrap().event,
+			Event::charity(RawEvent::AssetFundsAllocated(2, 7, 12, 18))
+		);
+		assert_noop!(
+			Charity::allocate_asset(RawOrigin::Root.into(), 7, 2, 19),
+			Error::<TestRuntime, DefaultInstance>::InsufficientAssetPot
+		);
+
+		// The native pot is untouched
+		assert_eq!(Charity::pot(), Balances::minimum_balance());
+	})
+}
+
+#[test]
+fn cant_donate_more_asset_than_held() {
+	new_test_ext().execute_with(|| {
+		AssetBalances::set(vec![((7, 1), 5)].into_iter().collect());
+		assert_noop!(
+			Charity::donate_asset(Origin::signed(1), 7, 6),
+			Error::<TestRuntime, DefaultInstance>::InsufficientFunds
+		);
+	})
+}
+
+#[test]
+fn allocation_limit_caps_each_allocation() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Charity::donate(Origin::signed(5), 15, vec![], None));
+		assert_ok!(Charity::set_allocation_limit(
+			RawOrigin::Root.into(),
+			Some(5)
+		));
+
+		assert_ok!(Charity::allocate(RawOrigin::Root.into(), 2, 5));
+		assert_noop!(
+			Charity::allocate(RawOrigin::Root.into(), 2, 6),
+			Error::<TestRuntime, DefaultInstance>::AllocationExceedsLimit
+		);
+
+		assert_ok!(Charity::set_allocation_limit(RawOrigin::Root.into(), None));
+		assert_eq!(
+			System::events().last().unwrap().event,
+			Event::charity(RawEvent::AllocationLimitSet(None))
+		);
+		assert_ok!(Charity::allocate(RawOrigin::Root.into(), 2, 6));
+		assert_eq!(Balances::free_balance(2), 11 + 5 + 6);
+	})
+}
+
+#[test]
+fn allocation_limit_caps_each_proportional_payout() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Charity::donate(Origin::signed(5), 15, vec![], None));
+		assert_ok!(Charity::set_allocation_limit(
+			RawOrigin::Root.into(),
+			Some(5)
+		));
+
+		// 25% of 16 is within the limit, but 50% is not, so neither recipient is paid
+		assert_noop!(
+			Charity::allocate_proportional(
+				RawOrigin::Root.into(),
+				vec![
+					(2, Perbill::from_percent(25)),
+					(3, Perbill::from_percent(50))
+				],
+				RemainderPolicy::KeepInPot,
+			),
+			Error::<TestRuntime, DefaultInstance>::AllocationExceedsLimit
+		);
+		assert_eq!(Charity::pot(), 16);
+
+		assert_ok!(Charity::allocate_proportional(
+			RawOrigin::Root.into(),
+			vec![
+				(2, Perbill::from_percent(25)),
+				(3, Perbill::from_percent(25))
+			],
+			RemainderPolicy::KeepInPot,
+		));
+		assert_eq!(Charity::pot(), 8);
+	})
+}
+
+#[test]
+fn proposed_allocation_executes_after_delay() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Charity::donate(Origin::signed(5), 15, vec![], None));
+		assert_ok!(Charity::propose_allocation(RawOrigin::Root.into(), 2, 6));
+		assert_eq!(Charity::pot(), 16 - 6);
+		assert_eq!(
+			System::events().last().unwrap().event,
+			Event::charity(RawEvent::AllocationQueued(0, 2, 6, 4))
+		);
+
+		run_to_block(3);
+		assert_eq!(Balances::free_balance(2), 11);
+		run_to_block(4);
+		assert_eq!(Balances::free_balance(2), 17);
+		assert_eq!(Charity::pending_allocation(0), None);
+		assert_eq!(Charity::locked_funds(), 0);
+		assert_eq!(Charity::pot(), 10);
+		assert_eq!(
+			System::events().last().unwrap().event,
+			Event::charity(RawEvent::AllocationExecuted(0, 2, 6))
+		);
+	})
+}
+
+#[test]
+fn proposed_allocation_can_be_cancelled() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Charity::donate(Origin::signed(5), 15, vec![], None));
+		assert_ok!(Charity::propose_allocation(RawOrigin::Root.into(), 2, 6));
+
+		run_to_block(3);
+		assert_ok!(Charity::cancel_allocation(RawOrigin::Root.into(), 0));
+		assert_eq!(Charity::pot(), 16);
+
+		run_to_block(6);
+		assert_eq!(Balances::free_balance(2), 11);
+		assert_noop!(
+			Charity::cancel_allocation(RawOrigin::Root.into(), 0),
+			Error::<TestRuntime, DefaultInstance>::NoSuchAllocation
+		);
+	})
+}
+
+#[test]
+fn sub_pots_are_independent() {
+	new_test_ext().execute_with(|| {
+		let first = Charity::sub_account_id(0);
+		let second = Charity::sub_account_id(1);
+		assert_ne!(first, second);
+		assert_ne!(first, Charity::account_id());
+
+		assert_ok!(Balances::transfer(Origin::signed(1), first, 4));
+		assert_ok!(Balances::transfer(Origin::signed(2), second, 7));
+		assert_eq!(Charity::sub_pot(0), 4);
+		assert_eq!(Charity::sub_pot(1), 7);
+		assert_eq!(Charity::sub_pot(2), 0);
+		assert_eq!(Charity::pot(), Balances::minimum_balance());
+	})
+}
+
+#[test]
+fn donation_tallies_error_rather_than_overflow() {
+	new_test_ext().execute_with(|| {
+		let whale = 6;
+		let big = u64::MAX - 200;
+		Balances::make_free_balance_be(&whale, u64::MAX - 100);
+		assert_ok!(Charity::donate(Origin::signed(whale), big, vec![], None));
+
+		// Allocations don't reduce the lifetime tallies, so the same funds can be donated again
+		assert_ok!(Charity::allocate(RawOrigin::Root.into(), whale, big));
+		assert_noop!(
+			Charity::donate(Origin::signed(whale), big, vec![], None),
+			Error::<TestRuntime, DefaultInstance>::ArithmeticOverflow
+		);
+		assert_eq!(Charity::total_donated(), big);
+		assert_eq!(Charity::donation_of(whale), big);
+	})
+}
+
+#[test]
+fn milestones_fire_once_in_order() {
+	new_test_ext().execute_with(|| {
+		Milestones::set(vec![20, 10]);
+		assert_ok!(Charity::donate(Origin::signed(2), 3, vec![], None));
+		// One donation takes the pot from 4 to 23, past both milestones
+		assert_ok!(Charity::donate(Origin::signed(5), 19, vec![], None));
+		assert_ok!(Charity::allocate(RawOrigin::Root.into(), 3, 10));
+		assert_ok!(Charity::donate(Origin::signed(1), 13, vec![], None));
+
+		let reached = System::events()
+			.into_iter()
+			.filter_map(|r| match r.event {
+				Event::charity(RawEvent::MilestoneReached(m)) => Some(m),
+				_ => None,
+			})
+			.collect::<Vec<_>>();
+		assert_eq!(reached, vec![10, 20]);
+		assert!(Charity::milestone_crossed(10));
+		assert!(!Charity::milestone_crossed(30));
+	})
+}
+
+#[test]
+fn allocate_if_sufficient_pays_when_the_pot_can_cover_it() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Charity::donate(Origin::signed(1), 10, vec![], None));
+
+		assert!(Charity::allocate_if_sufficient(&3, 6));
+		assert_eq!(Charity::pot(), 11 - 6);
+		assert_eq!(Balances::free_balance(3), 1 + 6);
+		assert!(System::events()
+			.iter()
+			.any(|r| r.event == Event::charity(RawEvent::FundsAllocated(3, 6, 5))));
+	})
+}
+
+#[test]
+fn allocate_if_sufficient_leaves_the_pot_alone_otherwise() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Charity::donate(Origin::signed(1), 10, vec![], None));
+
+		assert!(!Charity::allocate_if_sufficient(&3, 12));
+		assert_eq!(Charity::pot(), 11);
+		assert_eq!(Balances::free_balance(3), 1);
+	})
+}
+
+#[test]
+fn can_allocate_keeps_existential_deposit() {
+	use charity_runtime_api::runtime_decl_for_CharityApi::CharityApi;
+
+	new_test_ext().execute_with(|| {
+		assert_ok!(Charity::donate(Origin::signed(1), 10, vec![], None));
+		// The pot holds 11, one of which is its existential deposit
+		assert!(Charity::can_allocate(10));
+		assert!(!Charity::can_allocate(11));
+		assert!(<TestRuntime as CharityApi<Block, u128, u64>>::can_allocate(
+			10
+		));
+		assert!(!<TestRuntime as CharityApi<Block, u128, u64>>::can_allocate(11));
+	})
+}
+
+#[test]
+fn simulate_allocation_previews_the_remaining_pot() {
+	use charity_runtime_api::runtime_decl_for_CharityApi::CharityApi;
+
+	new_test_ext().execute_with(|| {
+		assert_ok!(Charity::donate(Origin::signed(1), 10, vec![], None));
+		let root = frame_support::storage_root();
+
+		assert_eq!(Charity::simulate_allocation(4), Ok(7));
+		assert_eq!(
+			<TestRuntime as CharityApi<Block, u128, u64>>::simulate_allocation(11),
+			Ok(0)
+		);
+		assert_eq!(Charity::pot(), 11);
+		assert_eq!(frame_support::storage_root(), root);
+	})
+}
+
+#[test]
+fn simulate_allocation_rejects_an_over_budget_amount() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Charity::donate(Origin::signed(1), 10, vec![], None));
+		let root = frame_support::storage_root();
+
+		assert_eq!(
+			Charity::simulate_allocation(12),
+			Err(Error::<TestRuntime, DefaultInstance>::InsufficientPot.into())
+		);
+		assert_eq!(Charity::pot(), 11);
+		assert_eq!(frame_support::storage_root(), root);
+	})
+}
+
+#[test]
+fn previews_refuse_while_paused() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Charity::donate(Origin::signed(1), 10, vec![], None));
+		assert_ok!(Charity::pause(RawOrigin::Root.into()));
+
+		assert_eq!(
+			Charity::simulate_allocation(4),
+			Err(Error::<TestRuntime, DefaultInstance>::PalletPaused.into())
+		);
+		assert!(!Charity::can_allocate(4));
+	})
+}
+
+#[test]
+fn previews_respect_the_allocation_limit() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Charity::donate(Origin::signed(1), 10, vec![], None));
+		assert_ok!(Charity::set_allocation_limit(
+			RawOrigin::Root.into(),
+			Some(3)
+		));
+
+		assert_eq!(Charity::simulate_allocation(3), Ok(8));
+		assert_eq!(
+			Charity::simulate_allocation(4),
+			Err(Error::<TestRuntime, DefaultInstance>::AllocationExceedsLimit.into())
+		);
+		assert!(Charity::can_allocate(3));
+		assert!(!Charity::can_allocate(4));
+	})
+}
+
+#[test]
+fn previews_respect_the_minimum_reserve() {
+	new_test_ext().execute_with(|| {
+		MinPotReserve::set(4);
+		assert_ok!(Charity::donate(Origin::signed(1), 10, vec![], None));
+
+		assert_eq!(Charity::simulate_allocation(7), Ok(4));
+		assert_eq!(
+			Charity::simulate_allocation(8),
+			Err(Error::<TestRuntime, DefaultInstance>::WouldBreachReserve.into())
+		);
+		assert!(Charity::can_allocate(7));
+		assert!(!Charity::can_allocate(8));
+	})
+}
+
+#[test]
+fn previews_respect_what_is_left_of_the_block_cap() {
+	new_test_ext().execute_with(|| {
+		MaxSpendPerBlock::set(6);
+		assert_ok!(Charity::donate(Origin::signed(1), 10, vec![], None));
+		assert_ok!(Charity::allocate(RawOrigin::Root.into(), 2, 4));
+
+		assert_eq!(Charity::simulate_allocation(2), Ok(5));
+		assert_eq!(
+			Charity::simulate_allocation(3),
+			Err(Error::<TestRuntime, DefaultInstance>::BlockSpendLimitReached.into())
+		);
+		assert!(Charity::can_allocate(2));
+		assert!(!Charity::can_allocate(3));
+	})
+}
+
+#[test]
+fn donations_on_behalf_credit_the_beneficiary() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Charity::donate_on_behalf(Origin::signed(5), 3, 10));
+		assert_eq!(Balances::free_balance(5), 19 - 10);
+		assert_eq!(Balances::free_balance(3), 1);
+		assert_eq!(Charity::donation_of(3), 10);
+		assert_eq!(Charity::donation_of(5), 0);
+
+		let events = System::events()
+			.into_iter()
+			.filter_map(|r| match r.event {
+				Event::charity(e @ RawEvent::DonationReceived(..))
+				| Event::charity(e @ RawEvent::DonatedOnBehalf(..)) => Some(e),
+				_ => None,
+			})
+			.collect::<Vec<_>>();
+		assert_eq!(
+			events,
+			vec![
+				RawEvent::DonationReceived(3, 10, 10, 11, Default::default(), 1, 10, 0),
+				RawEvent::DonatedOnBehalf(5, 3, 10),
+			]
+		);
+	})
+}
+
+#[test]
+fn only_the_absorb_fraction_of_an_imbalance_reaches_the_pot() {
+	new_test_ext().execute_with(|| {
+		AbsorbFraction::set(Perbill::from_percent(30));
+		Balances::make_free_balance_be(&7, 100);
+		let issuance = Balances::total_issuance();
+		let (slashed, _) = Balances::slash(&7, 100);
+
+		Charity::on_nonzero_unbalanced(slashed);
+
+		assert_eq!(Charity::pot(), 1 + 30);
+		// The other 70 were burned
+		assert_eq!(Balances::total_issuance(), issuance - 70);
+		assert!(System::events().iter().any(|r| r.event
+			== Event::charity(RawEvent::ImbalanceAbsorbed(30, 31, ImbalanceKind::Unknown))));
+	})
+}
+
+#[test]
+fn tagged_imbalances_record_their_source() {
+	new_test_ext().execute_with(|| {
+		Balances::make_free_balance_be(&7, 10);
+		let (slashed, _) = Balances::slash(&7, 10);
+
+		Charity::absorb_imbalance(slashed, ImbalanceKind::Slash);
+
+		assert_eq!(Charity::pot(), 1 + 10);
+		assert!(System::events().iter().any(|r| r.event
+			== Event::charity(RawEvent::ImbalanceAbsorbed(10, 11, ImbalanceKind::Slash))));
+	})
+}
+
+#[test]
+fn slashes_routed_through_the_adapter_reach_the_pot() {
+	new_test_ext().execute_with(|| {
+		let (slashed, _) = Balances::slash(&5, 9);
+		charity::SlashToCharity::<TestRuntime>::on_unbalanced(slashed);
+
+		assert_eq!(Charity::pot(), 1 + 9);
+		assert!(System::events()
+			.iter()
+			.any(|r| r.event
+				== Event::charity(RawEvent::ImbalanceAbsorbed(9, 10, ImbalanceKind::Slash))));
+
+		// The adapter feeds the instance it names
+		let (slashed, _) = Balances::slash(&5, 4);
+		charity::SlashToCharity::<TestRuntime, charity::Instance1>::on_unbalanced(slashed);
+		assert_eq!(Endowment::pot(), 4);
+		assert_eq!(Charity::pot(), 10);
+	})
+}
+
+#[test]
+fn positive_imbalances_are_paid_from_the_pot() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Charity::donate(Origin::signed(1), 10, vec![], None));
+		// A rebate of 4 is minted for user 2 and backed by the pot
+		let issuance = Balances::total_issuance();
+		let rebate = Balances::deposit_creating(&2, 4);
+		charity::SettleFromPot::<TestRuntime>::on_nonzero_unbalanced(rebate);
+
+		assert_eq!(Charity::pot(), 11 - 4);
+		assert_eq!(Balances::free_balance(2), 15);
+		assert_eq!(Balances::total_issuance(), issuance);
+		assert!(System::events()
+			.iter()
+			.any(|r| r.event == Event::charity(RawEvent::PositiveImbalanceSettled(4, 7))));
+
+		// Rebates the pot can't cover are left minted
+		let rebate = Balances::deposit_creating(&2, 20);
+		charity::SettleFromPot::<TestRuntime>::on_nonzero_unbalanced(rebate);
+		assert_eq!(Charity::pot(), 7);
+		assert_eq!(Balances::free_balance(2), 35);
+		assert_eq!(Balances::total_issuance(), issuance + 20);
+	})
+}
+
+#[test]
+fn positive_imbalances_are_paid_from_a_reserved_pot() {
+	new_test_ext().execute_with(|| {
+		UseReserved::set(true);
+		let pot_account = Charity::account_id();
+		assert_ok!(Charity::donate(Origin::signed(1), 10, vec![], None));
+
+		let rebate = Balances::deposit_creating(&2, 4);
+		charity::SettleFromPot::<TestRuntime>::on_nonzero_unbalanced(rebate);
+
+		assert_eq!(Charity::pot(), 6);
+		assert_eq!(Balances::reserved_balance(pot_account), 6);
+		assert_eq!(Balances::free_balance(pot_account), 1);
+	})
+}
+
+#[test]
+fn allowlist_restricts_donors_only_when_enabled() {
+	new_test_ext().execute_with(|| {
+		// Disabled by default, so anyone may donate
+		assert_ok!(Charity::donate(Origin::signed(1), 2, vec![], None));
+
+		assert_ok!(Charity::set_allowlist_enabled(RawOrigin::Root.into(), true));
+		assert_ok!(Charity::add_to_allowlist(RawOrigin::Root.into(), 1));
+
+		// An allowed donor
+		assert_ok!(Charity::donate(Origin::signed(1), 2, vec![], None));
+		assert_eq!(Charity::donation_of(1), 4);
+
+		// A blocked donor, also when giving for or through someone else
+		assert_noop!(
+			Charity::donate(Origin::signed(2), 2, vec![], None),
+			Error::<TestRuntime, DefaultInstance>::DonorNotAllowed
+		);
+		assert_noop!(
+			Charity::donate_on_behalf(Origin::signed(1), 2, 2),
+			Error::<TestRuntime, DefaultInstance>::DonorNotAllowed
+		);
+		assert_noop!(
+			Charity::donate_on_behalf(Origin::signed(2), 1, 2),
+			Error::<TestRuntime, DefaultInstance>::DonorNotAllowed
+		);
+
+		// Removal blocks the donor again
+		assert_ok!(Charity::remove_from_allowlist(RawOrigin::Root.into(), 1));
+		assert_noop!(
+			Charity::donate(Origin::signed(1), 2, vec![], None),
+			Error::<TestRuntime, DefaultInstance>::DonorNotAllowed
+		);
+
+		// Turning the mode off opens donations up
+		assert_ok!(Charity::set_allowlist_enabled(
+			RawOrigin::Root.into(),
+			false
+		));
+		assert_ok!(Charity::donate(Origin::signed(2), 2, vec![], None));
+		assert_noop!(
+			Charity::add_to_allowlist(Origin::signed(1), 1),
+			sp_runtime::DispatchError::BadOrigin
+		);
+	})
+}
+
+#[test]
+fn allocate_batch_isolates_failed_payments() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Charity::donate(Origin::signed(1), 10, vec![], None));
+
+		// The second payment is more than is left in the pot by then
+		assert_ok!(Charity::allocate_batch(
+			RawOrigin::Root.into(),
+			vec![(2, 6), (3, 7), (4, 3)]
+		));
+
+		assert_eq!(Balances::free_balance(2), 11 + 6);
+		assert_eq!(Balances::free_balance(3), 1);
+		assert_eq!(Balances::free_balance(4), 3 + 3);
+		assert_eq!(Charity::pot(), 2);
+
+		let events = System::events()
+			.into_iter()
+			.filter_map(|r| match r.event {
+				Event::charity(e @ RawEvent::FundsAllocated(..))
+				| Event::charity(e @ RawEvent::AllocationFailed(..)) => Some(e),
+				_ => None,
+			})
+			.collect::<Vec<_>>();
+		assert_eq!(
+			events,
+			vec![
+				RawEvent::FundsAllocated(2, 6, 5),
+				RawEvent::AllocationFailed(3, 7),
+				RawEvent::FundsAllocated(4, 3, 2),
+			]
+		);
+
+		assert_noop!(
+			Charity::allocate_batch(RawOrigin::Root.into(), vec![(2, 1); 5]),
+			Error::<TestRuntime, DefaultInstance>::BatchTooLarge
+		);
+	})
+}
+
+#[test]
+fn pot_history_keeps_the_latest_snapshots() {
+	new_test_ext().execute_with(|| {
+		Balances::make_free_balance_be(&7, 100);
+		for block in 1..=20 {
+			System::set_block_number(block);
+			Charity::on_initialize(block);
+			assert_ok!(Charity::donate(Origin::signed(7), 2, vec![], None));
+			Charity::on_finalize(block);
+		}
+
+		// Only the last three snapshots are kept
+		assert_eq!(Charity::snapshot_blocks(), vec![10, 15, 20]);
+		assert_eq!(Charity::pot_at(5), None);
+		assert_eq!(Charity::pot_at(10), Some(1 + 2 * 10));
+		assert_eq!(Charity::pot_at(15), Some(1 + 2 * 15));
+		assert_eq!(Charity::pot_at(20), Some(1 + 2 * 20));
+		assert_eq!(Charity::pot_at(11), None);
+	})
+}
+
+#[test]
+fn top_donors_are_ordered_by_contribution() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Charity::donate(Origin::signed(1), 3, vec![], None));
+		assert_ok!(Charity::donate(Origin::signed(2), 5, vec![], None));
+		assert_ok!(Charity::donate(Origin::signed(5), 4, vec![], None));
+		assert_eq!(Charity::top_donors(), vec![(2, 5), (5, 4), (1, 3)]);
+
+		// Ties keep the earlier donor ahead
+		assert_ok!(Charity::donate(Origin::signed(1), 2, vec![], None));
+		assert_eq!(Charity::top_donors(), vec![(2, 5), (1, 5), (5, 4)]);
+	})
+}
+
+#[test]
+fn top_donors_drops_the_smallest_when_full() {
+	new_test_ext().execute_with(|| {
+		Balances::make_free_balance_be(&6, 20);
+		assert_ok!(Charity::donate(Origin::signed(1), 3, vec![], None));
+		assert_ok!(Charity::donate(Origin::signed(2), 5, vec![], None));
+		assert_ok!(Charity::donate(Origin::signed(5), 4, vec![], None));
+
+		// Too small to make the board
+		assert_ok!(Charity::donate(Origin::signed(6), 2, vec![], None));
+		assert_eq!(Charity::top_donors(), vec![(2, 5), (5, 4), (1, 3)]);
+
+		// Big enough to push user 1 off
+		assert_ok!(Charity::donate(Origin::signed(6), 8, vec![], None));
+		assert_eq!(Charity::top_donors(), vec![(6, 10), (2, 5), (5, 4)]);
+	})
+}
+
+#[test]
+fn anonymous_donors_stay_off_the_leaderboard() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Charity::hide_from_leaderboard(Origin::signed(5)));
+		assert_ok!(Charity::donate(Origin::signed(5), 9, vec![], None));
+		assert_ok!(Charity::donate(Origin::signed(1), 3, vec![], None));
+
+		// The donation is counted everywhere but the board
+		assert_eq!(Charity::pot(), 1 + 9 + 3);
+		assert_eq!(Charity::donation_of(5), 9);
+		assert_eq!(Charity::total_donated(), 12);
+		assert_eq!(Charity::top_donors(), vec![(1, 3)]);
+		assert_eq!(Charity::donation_rank(&5), None);
+	})
+}
+
+#[test]
+fn donors_can_leave_and_rejoin_the_leaderboard() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Charity::donate(Origin::signed(1), 3, vec![], None));
+		assert_ok!(Charity::donate(Origin::signed(2), 5, vec![], None));
+
+		assert_ok!(Charity::hide_from_leaderboard(Origin::signed(2)));
+		assert_eq!(Charity::top_donors(), vec![(1, 3)]);
+		assert!(System::events()
+			.iter()
+			.any(|r| r.event == Event::charity(RawEvent::AnonymitySet(2, true))));
+
+		assert_ok!(Charity::show_on_leaderboard(Origin::signed(2)));
+		assert!(!Charity::is_anonymous(2));
+		assert_eq!(Charity::top_donors(), vec![(2, 5), (1, 3)]);
+	})
+}
+
+#[test]
+fn top_donors_move_up_and_down_with_their_totals() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Charity::donate(Origin::signed(1), 3, vec![], None));
+		assert_ok!(Charity::donate(Origin::signed(2), 5, vec![], None));
+		assert_ok!(Charity::donate(Origin::signed(5), 4, vec![], None));
+
+		assert_ok!(Charity::donate(Origin::signed(1), 3, vec![], None));
+		assert_eq!(Charity::top_donors(), vec![(1, 6), (2, 5), (5, 4)]);
+
+		assert_ok!(Charity::withdraw_donation(Origin::signed(2), 2));
+		assert_eq!(Charity::top_donors(), vec![(1, 6), (5, 4), (2, 3)]);
+		assert_ok!(Charity::withdraw_donation(Origin::signed(2), 3));
+		assert_eq!(Charity::top_donors(), vec![(1, 6), (5, 4)]);
+
+		assert_ok!(Charity::refund_all(RawOrigin::Root.into(), 2));
+		assert!(Charity::top_donors().is_empty());
+	})
+}
+
+#[test]
+fn allocation_origin_can_be_other_than_root() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Charity::donate(Origin::signed(1), 10, vec![], None));
+
+		// The configured treasurer may allocate, as may root
+		assert_ok!(Charity::allocate(Origin::signed(42), 2, 3));
+		assert_ok!(Charity::allocate(RawOrigin::Root.into(), 2, 3));
+		assert_eq!(Balances::free_balance(2), 11 + 6);
+
+		// Anyone else may not
+		assert_noop!(
+			Charity::allocate(Origin::signed(1), 2, 3),
+			sp_runtime::DispatchError::BadOrigin
+		);
+	})
+}
+
+#[test]
+fn donations_with_a_used_nonce_are_rejected() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Charity::donate(Origin::signed(1), 3, vec![], Some(7)));
+		assert_eq!(Balances::free_balance(1), 10);
+
+		assert_noop!(
+			Charity::donate(Origin::signed(1), 3, vec![], Some(7)),
+			Error::<TestRuntime, DefaultInstance>::DuplicateDonation
+		);
+		assert_eq!(Balances::free_balance(1), 10);
+		assert_eq!(Charity::pot(), 1 + 3);
+
+		// Another nonce, another donor with the same nonce, or no nonce at all are fine
+		assert_ok!(Charity::donate(Origin::signed(1), 3, vec![], Some(8)));
+		assert_ok!(Charity::donate(Origin::signed(2), 3, vec![], Some(7)));
+		assert_ok!(Charity::donate(Origin::signed(1), 3, vec![], None));
+		assert_ok!(Charity::donate(Origin::signed(1), 3, vec![], None));
+		assert_eq!(Charity::donation_of(1), 12);
+	})
+}
+
+#[test]
+fn failed_donations_do_not_use_up_their_nonce() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(
+			Charity::donate(Origin::signed(1), 20, vec![], Some(1)),
+			Error::<TestRuntime, DefaultInstance>::InsufficientFunds
+		);
+		assert_ok!(Charity::donate(Origin::signed(1), 3, vec![], Some(1)));
+	})
+}
+
+#[test]
+fn donation_rank_follows_the_leaderboard() {
+	use charity_runtime_api::runtime_decl_for_CharityApi::CharityApi;
+
+	new_test_ext().execute_with(|| {
+		Balances::make_free_balance_be(&6, 20);
+		assert_ok!(Charity::donate(Origin::signed(2), 5, vec![], None));
+		assert_ok!(Charity::donate(Origin::signed(5), 4, vec![], None));
+		assert_ok!(Charity::donate(Origin::signed(1), 3, vec![], None));
+		assert_ok!(Charity::donate(Origin::signed(6), 2, vec![], None));
+
+		assert_eq!(Charity::donation_rank(&2), Some(1));
+		assert_eq!(Charity::donation_rank(&5), Some(2));
+		assert_eq!(Charity::donation_rank(&1), Some(3));
+		// User 6 donated but is not on the leaderboard, user 3 never donated
+		assert_eq!(Charity::donation_rank(&6), None);
+		assert_eq!(Charity::donation_rank(&3), None);
+
+		assert_eq!(
+			<TestRuntime as CharityApi<Block, u128, u64>>::donation_rank(5),
+			Some(2)
+		);
+		assert_eq!(
+			<TestRuntime as CharityApi<Block, u128, u64>>::donation_rank(3),
+			None
+		);
+	})
+}
+
+#[test]
+fn cause_allocations_report_the_remaining_tally() {
+	new_test_ext().execute_with(|| {
+		let cause = b"wells".to_vec();
+		assert_ok!(Charity::donate_to_cause(
+			Origin::signed(1),
+			cause.clone(),
+			4
+		));
+		assert_ok!(Charity::donate_to_cause(
+			Origin::signed(2),
+			cause.clone(),
+			3
+		));
+		assert_ok!(Charity::donate_to_cause(
+			Origin::signed(5),
+			cause.clone(),
+			6
+		));
+		assert_eq!(Charity::cause_balance(&cause), 13);
+
+		assert_ok!(Charity::allocate_from_cause(
+			RawOrigin::Root.into(),
+			cause.clone(),
+			3,
+			5
+		));
+		assert_eq!(Charity::cause_balance(&cause), 8);
+		assert_eq!(
+			System::events().last().unwrap().event,
+			Event::charity(RawEvent::FundsAllocatedFromCause(
+				cause.try_into().unwrap(),
+				3,
+				5,
+				8
+			))
+		);
+	})
+}
+
+#[test]
+fn donate_weight_grows_with_the_memo() {
+	use frame_support::weights::GetDispatchInfo;
+
+	let weight_for = |memo_len: usize| {
+		charity::Call::<TestRuntime>::donate(2, vec![b'm'; memo_len], None)
+			.get_dispatch_info()
+			.weight
+	};
+	assert!(weight_for(0) < weight_for(8));
+	assert!(weight_for(8) < weight_for(16));
+
+	new_test_ext().execute_with(|| {
+		// An oversized memo is turned away before any funds or tallies are touched
+		assert_noop!(
+			Charity::donate(Origin::signed(1), 2, vec![b'm'; 17], None),
+			Error::<TestRuntime, DefaultInstance>::MemoTooLong
+		);
+	})
+}
+
+#[test]
+fn top_ups_grow_the_pot_without_donor_records() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Charity::fund_pot_from_account(RawOrigin::Root.into(), 5, 9));
+
+		assert_eq!(Balances::free_balance(5), 19 - 9);
+		assert_eq!(Charity::pot(), 1 + 9);
+		assert_eq!(Charity::donation_of(5), 0);
+		assert_eq!(Charity::total_donated(), 0);
+		assert!(Charity::top_donors().is_empty());
+		assert_eq!(
+			System::events().last().unwrap().event,
+			Event::charity(RawEvent::PotToppedUp(5, 9, 10))
+		);
+
+		assert_noop!(
+			Charity::fund_pot_from_account(Origin::signed(5), 5, 2),
+			sp_runtime::DispatchError::BadOrigin
+		);
+		assert_noop!(
+			Charity::fund_pot_from_account(RawOrigin::Root.into(), 5, 20),
+			Error::<TestRuntime, DefaultInstance>::InsufficientFunds
+		);
+	})
+}
+
+#[test]
+fn root_can_force_a_donation_from_any_account() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Charity::force_donate(RawOrigin::Root.into(), 5, 9));
+
+		assert_eq!(Balances::free_balance(5), 19 - 9);
+		assert_eq!(Charity::pot(), 1 + 9);
+		assert_eq!(Charity::donation_of(5), 9);
+		assert_eq!(Charity::total_donated(), 9);
+		assert_eq!(Charity::donation_rank(&5), Some(1));
+		assert!(System::events().iter().any(|r| r.event
+			== Event::charity(RawEvent::DonationReceived(
+				5,
+				9,
+				9,
+				10,
+				Default::default(),
+				1,
+				9,
+				0
+			))));
+
+		assert_noop!(
+			Charity::force_donate(Origin::signed(1), 5, 2),
+			sp_runtime::DispatchError::BadOrigin
+		);
+	})
+}
+
+#[test]
+fn merging_donor_records_combines_their_contributions() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Charity::donate(Origin::signed(1), 3, vec![], None));
+		assert_ok!(Charity::donate(Origin::signed(2), 5, vec![], None));
+		assert_ok!(Charity::donate(Origin::signed(5), 4, vec![], None));
+		assert_eq!(Charity::top_donors(), vec![(2, 5), (5, 4), (1, 3)]);
+
+		assert_ok!(Charity::merge_donor_records(RawOrigin::Root.into(), 5, 1));
+		assert_eq!(Charity::donation_of(1), 3 + 4);
+		assert_eq!(Charity::donation_of(5), 0);
+		assert_eq!(Charity::top_donors(), vec![(1, 7), (2, 5)]);
+		assert_eq!(Charity::distinct_donors(), 2);
+		// Nothing but the records has changed
+		assert_eq!(Charity::pot(), 1 + 12);
+		assert_eq!(Charity::total_donated(), 12);
+		assert_eq!(
+			System::events().last().unwrap().event,
+			Event::charity(RawEvent::DonorRecordsMerged(5, 1, 4))
+		);
+	})
+}
+
+#[test]
+fn donor_records_can_move_to_a_fresh_account() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Charity::donate(Origin::signed(1), 3, vec![], None));
+
+		assert_ok!(Charity::merge_donor_records(RawOrigin::Root.into(), 1, 7));
+		assert_eq!(Charity::donation_of(7), 3);
+		assert_eq!(Charity::top_donors(), vec![(7, 3)]);
+		assert_eq!(Charity::distinct_donors(), 1);
+
+		assert_noop!(
+			Charity::merge_donor_records(RawOrigin::Root.into(), 7, 7),
+			Error::<TestRuntime, DefaultInstance>::CannotMergeIntoSelf
+		);
+		assert_noop!(
+			Charity::merge_donor_records(Origin::signed(7), 7, 1),
+			sp_runtime::DispatchError::BadOrigin
+		);
+	})
+}
+
+#[test]
+fn allocations_cannot_spend_funds_owed_to_vesting() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Charity::donate(Origin::signed(1), 10, vec![], None));
+		assert_ok!(Charity::allocate_with_vesting(
+			RawOrigin::Root.into(),
+			3,
+			6,
+			2,
+			5
+		));
+		assert_eq!(Charity::available_to_allocate(), 11 - 6);
+
+		assert_err!(
+			Charity::allocate(RawOrigin::Root.into(), 2, 6),
+			Error::<TestRuntime, DefaultInstance>::InsufficientPot
+		);
+		assert_ok!(Charity::allocate(RawOrigin::Root.into(), 2, 5));
+		assert_eq!(Charity::vesting_schedule_of(3).unwrap().remaining, 6);
+	})
+}
+
+#[test]
+fn allocation_handler_hears_only_successful_allocations() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Charity::donate(Origin::signed(1), 10, vec![], None));
+
+		assert_ok!(Charity::allocate(RawOrigin::Root.into(), 2, 4));
+		assert_err!(
+			Charity::allocate(RawOrigin::Root.into(), 3, 50),
+			Error::<TestRuntime, DefaultInstance>::InsufficientPot
+		);
+
+		assert_eq!(AllocationsSeen::get(), vec![(2, 4)]);
+	})
+}
+
+#[test]
+fn allocation_handler_hears_every_allocation_route() {
+	new_test_ext().execute_with(|| {
+		AllocationFee::set(Perbill::from_percent(50));
+		assert_ok!(Charity::donate_to_cause(
+			Origin::signed(5),
+			b"education".to_vec(),
+			15
+		));
+
+		assert_ok!(Charity::allocate_batch(
+			RawOrigin::Root.into(),
+			vec![(2, 2), (3, 50)]
+		));
+		assert_ok!(Charity::allocate_from_cause(
+			RawOrigin::Root.into(),
+			b"education".to_vec(),
+			3,
+			4
+		));
+		assert_ok!(Charity::allocate_proportional(
+			RawOrigin::Root.into(),
+			vec![(4, Perbill::from_percent(20))],
+			RemainderPolicy::KeepInPot,
+		));
+		assert_ok!(Charity::allocate_all(RawOrigin::Root.into(), 2, true));
+
+		// Each recipient is reported once, with what reached them; the fee is not an allocation
+		assert_eq!(AllocationsSeen::get(), vec![(2, 1), (3, 2), (4, 1), (2, 4)]);
+	})
+}
+
+#[test]
+fn allocate_all_can_leave_the_existential_deposit() {
+	new_test_ext().execute_with(|| {
+		let pot_account = Charity::account_id();
+		assert_ok!(Charity::donate(Origin::signed(1), 10, vec![], None));
+
+		assert_ok!(Charity::allocate_all(RawOrigin::Root.into(), 2, true));
+		assert_eq!(Balances::free_balance(2), 11 + 10);
+		assert_eq!(Charity::pot(), Balances::minimum_balance());
+		assert_eq!(Balances::free_balance(pot_account), 1);
+
+		// Nothing more can be paid out while keeping the pot alive
+		assert_noop!(
+			Charity::allocate_all(RawOrigin::Root.into(), 2, true),
+			Error::<TestRuntime, DefaultInstance>::InsufficientPot
+		);
+	})
+}
+
+#[test]
+fn allocate_all_can_drain_the_pot_entirely() {
+	new_test_ext().execute_with(|| {
+		let pot_account = Charity::account_id();
+		assert_ok!(Charity::donate(Origin::signed(1), 10, vec![], None));
+
+		assert_ok!(Charity::allocate_all(RawOrigin::Root.into(), 2, false));
+		assert_eq!(Balances::free_balance(2), 11 + 11);
+		assert_eq!(Charity::pot(), 0);
+		assert_eq!(Balances::total_balance(&pot_account), 0);
+		assert_eq!(
+			System::events().last().unwrap().event,
+			Event::charity(RawEvent::FundsAllocated(2, 11, 0))
+		);
+	})
+}
+
+#[test]
+fn donation_streaks_grow_within_the_window() {
+	new_test_ext().execute_with(|| {
+		StreakThresholds::set(vec![3]);
+		let donate_at = |block| {
+			System::set_block_number(block);
+			assert_ok!(Charity::donate(Origin::signed(5), 2, vec![], None));
+		};
+
+		donate_at(1);
+		assert_eq!(Charity::donation_streak(5), 1);
+		// A second donation in the same block doesn't count
+		donate_at(1);
+		assert_eq!(Charity::donation_streak(5), 1);
+		donate_at(2);
+		donate_at(4);
+		assert_eq!(Charity::donation_streak(5), 3);
+		assert_eq!(Charity::last_donation_block(5), 4);
+		assert!(System::events()
+			.iter()
+			.any(|r| r.event == Event::charity(RawEvent::StreakExtended(5, 3))));
+	})
+}
+
+#[test]
+fn donation_streaks_reset_outside_the_window() {
+	new_test_ext().execute_with(|| {
+		StreakThresholds::set(vec![3]);
+		let donate_at = |block| {
+			System::set_block_number(block);
+			assert_ok!(Charity::donate(Origin::signed(5), 2, vec![], None));
+		};
+
+		donate_at(1);
+		donate_at(3);
+		assert_eq!(Charity::donation_streak(5), 2);
+		donate_at(6);
+		assert_eq!(Charity::donation_streak(5), 1);
+		donate_at(7);
+		assert_eq!(Charity::donation_streak(5), 2);
+		assert!(!System::events()
+			.iter()
+			.any(|r| matches!(r.event, Event::charity(RawEvent::StreakExtended(..)))));
+	})
+}
+
+#[test]
+fn sweeping_only_works_while_paused() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Charity::donate(Origin::signed(1), 10, vec![], None));
+
+		assert_noop!(
+			Charity::sweep_to_account(RawOrigin::Root.into(), 7),
+			Error::<TestRuntime, DefaultInstance>::NotPaused
+		);
+
+		assert_ok!(Charity::pause(RawOrigin::Root.into()));
+		assert_noop!(
+			Charity::sweep_to_account(Origin::signed(1), 7),
+			sp_runtime::DispatchError::BadOrigin
+		);
+		assert_ok!(Charity::sweep_to_account(RawOrigin::Root.into(), 7));
+		assert_eq!(Balances::free_balance(7), 11);
+		assert_eq!(Charity::pot(), 0);
+		assert_eq!(
+			System::events().last().unwrap().event,
+			Event::charity(RawEvent::PotSwept(7, 11))
+		);
+	})
+}
+
+#[test]
+fn pot_account_follows_the_configured_id() {
+	new_test_ext().execute_with(|| {
+		let pot_for = |id: [u8; 8]| {
+			CharityModuleId::set(ModuleId(id));
+			Charity::account_id()
+		};
+
+		let first = pot_for(*b"Disaster");
+		let second = pot_for(*b"Endowmnt");
+		assert_ne!(first, second);
+		assert_eq!(first, ModuleId(*b"Disaster").into_account());
+		assert_eq!(second, ModuleId(*b"Endowmnt").into_account());
+	})
+}
+
+#[test]
+fn instances_keep_separate_pots_and_totals() {
+	new_test_ext().execute_with(|| {
+		assert_ne!(Charity::account_id(), Endowment::account_id());
+
+		assert_ok!(Charity::donate(Origin::signed(1), 10, vec![], None));
+		assert_ok!(Endowment::donate(Origin::signed(2), 4, vec![], None));
+
+		assert_eq!(Charity::pot(), 1 + 10);
+		assert_eq!(Endowment::pot(), 4);
+		assert_eq!(Charity::total_donated(), 10);
+		assert_eq!(Endowment::total_donated(), 4);
+		assert_eq!(Charity::donation_of(2), 0);
+		assert_eq!(Endowment::donation_of(1), 0);
+
+		assert_ok!(Endowment::allocate(RawOrigin::Root.into(), 3, 4));
+		assert_eq!(Endowment::pot(), 0);
+		assert_eq!(Charity::pot(), 11);
+		assert!(System::events()
+			.iter()
+			.any(|r| r.event == Event::charity_Instance1(RawEvent::FundsAllocated(3, 4, 0))));
+	})
+}
+
+#[test]
+fn new_chains_start_at_the_latest_storage_version() {
+	new_test_ext().execute_with(|| {
+		assert_eq!(Charity::storage_version(), StorageVersion::V2);
+
+		Charity::on_runtime_upgrade();
+		assert!(System::events().is_empty());
+	})
+}
+
+#[test]
+fn upgrade_fills_in_total_donated() {
+	new_test_ext().execute_with(|| {
+		// Storage as an older version of the pallet left it
+		charity::CurrentStorageVersion::<DefaultInstance>::put(StorageVersion::V1);
+		charity::Donations::<TestRuntime>::insert(1, 5);
+		charity::Donations::<TestRuntime>::insert(2, 7);
+		charity::TotalDonated::<TestRuntime>::kill();
+
+		Charity::on_runtime_upgrade();
+
+		assert_eq!(Charity::total_donated(), 12);
+		assert_eq!(Charity::storage_version(), StorageVersion::V2);
+		assert_eq!(
+			System::events()[0].event,
+			Event::charity(RawEvent::MigrationCompleted(StorageVersion::V2))
+		);
+
+		// Running it again changes nothing
+		charity::TotalDonated::<TestRuntime>::put(20);
+		Charity::on_runtime_upgrade();
+		assert_eq!(Charity::total_donated(), 20);
+	})
+}
+
+#[test]
+fn upgrade_without_donor_records_seeds_from_the_pot() {
+	new_test_ext().execute_with(|| {
+		charity::CurrentStorageVersion::<DefaultInstance>::put(StorageVersion::V1);
+		let _ = Balances::deposit_creating(&Charity::account_id(), 8);
+
+		Charity::on_runtime_upgrade();
+		assert_eq!(Charity::total_donated(), 1 + 8);
+	})
+}
+
+#[cfg(feature = "try-runtime")]
+#[test]
+fn upgrade_checks_pass_around_a_sound_migration() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Charity::donate(Origin::signed(1), 10, vec![], None));
+		charity::CurrentStorageVersion::<DefaultInstance>::put(StorageVersion::V1);
+
+		assert_ok!(Charity::pre_upgrade());
+		Charity::on_runtime_upgrade();
+		assert_ok!(Charity::post_upgrade());
+	})
+}
+
+#[cfg(feature = "try-runtime")]
+#[test]
+fn post_upgrade_catches_corrupted_totals() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Charity::donate(Origin::signed(1), 10, vec![], None));
+		Charity::on_runtime_upgrade();
+
+		// A faulty migration that lost part of the lifetime total
+		charity::TotalDonated::<TestRuntime>::put(4);
+		assert_eq!(
+			Charity::post_upgrade(),
+			Err("donor credits exceed TotalDonated")
+		);
+	})
+}
+
+#[cfg(feature = "try-runtime")]
+#[test]
+fn post_upgrade_catches_an_unmigrated_version() {
+	new_test_ext().execute_with(|| {
+		charity::CurrentStorageVersion::<DefaultInstance>::put(StorageVersion::V1);
+		assert_eq!(
+			Charity::post_upgrade(),
+			Err("charity storage was not migrated to V2")
+		);
+	})
+}
+
+#[test]
+fn donations_are_allowed_once_the_cooldown_has_passed() {
+	new_test_ext().execute_with(|| {
+		DonationCooldown::set(3);
+		System::set_block_number(1);
+		assert_ok!(Charity::donate(Origin::signed(1), 2, vec![], None));
+
+		System::set_block_number(4);
+		assert_ok!(Charity::donate(Origin::signed(1), 2, vec![], None));
+		assert_eq!(Charity::donation_of(1), 4);
+	})
+}
+
+#[test]
+fn donations_inside_the_cooldown_are_rejected() {
+	new_test_ext().execute_with(|| {
+		DonationCooldown::set(3);
+		System::set_block_number(1);
+		assert_ok!(Charity::donate(Origin::signed(1), 2, vec![], None));
+
+		System::set_block_number(3);
+		assert_noop!(
+			Charity::donate(Origin::signed(1), 2, vec![], None),
+			Error::<TestRuntime, DefaultInstance>::DonationTooSoon
+		);
+		// Other donors are unaffected
+		assert_ok!(Charity::donate(Origin::signed(2), 2, vec![], None));
+	})
+}
+
+#[test]
+fn the_cooldown_covers_every_donation_route() {
+	new_test_ext().execute_with(|| {
+		DonationCooldown::set(3);
+		System::set_block_number(1);
+		assert_ok!(Charity::donate(Origin::signed(1), 2, vec![], None));
+
+		System::set_block_number(2);
+		assert_noop!(
+			Charity::donate_to_cause(Origin::signed(1), b"education".to_vec(), 2),
+			Error::<TestRuntime, DefaultInstance>::DonationTooSoon
+		);
+		// Donating on someone's behalf counts against the beneficiary
+		assert_noop!(
+			Charity::donate_on_behalf(Origin::signed(5), 1, 2),
+			Error::<TestRuntime, DefaultInstance>::DonationTooSoon
+		);
+	})
+}
+
+#[test]
+fn zero_cooldown_allows_back_to_back_donations() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Charity::donate(Origin::signed(1), 2, vec![], None));
+		assert_ok!(Charity::donate(Origin::signed(1), 2, vec![], None));
+		assert_eq!(Charity::donation_of(1), 4);
+	})
+}
+
+#[test]
+fn donations_are_capped_per_period() {
+	new_test_ext().execute_with(|| {
+		DonationPeriod::set(10);
+		PerDonorPeriodCap::set(10);
+		System::set_block_number(1);
+
+		// Up to the cap is fine
+		assert_ok!(Charity::donate(Origin::signed(5), 6, vec![], None));
+		assert_ok!(Charity::donate(Origin::signed(5), 4, vec![], None));
+		assert_eq!(Charity::donated_in_period(5), (0, 10));
+
+		// Past it is not, even late in the period
+		System::set_block_number(9);
+		assert_noop!(
+			Charity::donate(Origin::signed(5), 2, vec![], None),
+			Error::<TestRuntime, DefaultInstance>::PeriodCapExceeded
+		);
+		// The cap is per donor
+		assert_ok!(Charity::donate(Origin::signed(1), 10, vec![], None));
+
+		// A new period starts a fresh tally
+		System::set_block_number(10);
+		assert_ok!(Charity::donate(Origin::signed(5), 2, vec![], None));
+		assert_eq!(Charity::donated_in_period(5), (1, 2));
+		assert_eq!(Charity::donation_of(5), 12);
+	})
+}
+
+#[test]
+fn a_single_donation_over_the_period_cap_is_rejected() {
+	new_test_ext().execute_with(|| {
+		DonationPeriod::set(10);
+		PerDonorPeriodCap::set(10);
+		assert_noop!(
+			Charity::donate(Origin::signed(5), 11, vec![], None),
+			Error::<TestRuntime, DefaultInstance>::PeriodCapExceeded
+		);
+
+		// A zero period turns the cap off
+		DonationPeriod::set(0);
+		assert_ok!(Charity::donate(Origin::signed(5), 11, vec![], None));
+	})
+}
+
+#[test]
+fn allocate_percentage_tracks_the_live_pot() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Charity::donate(Origin::signed(1), 10, vec![], None));
+
+		// A quarter of 11 is 2.75, which rounds to 3
+		assert_ok!(Charity::allocate_percentage(
+			RawOrigin::Root.into(),
+			2,
+			Perbill::from_percent(25)
+		));
+		assert_eq!(Balances::free_balance(2), 11 + 3);
+		assert_eq!(Charity::pot(), 8);
+
+		// The same share of the smaller pot is a smaller amount
+		assert_ok!(Charity::allocate_percentage(
+			RawOrigin::Root.into(),
+			2,
+			Perbill::from_percent(25)
+		));
+		assert_eq!(Balances::free_balance(2), 11 + 3 + 2);
+		assert!(System::events()
+			.iter()
+			.any(|r| r.event == Event::charity(RawEvent::FundsAllocated(2, 2, 6))));
+	})
+}
+
+#[test]
+fn allocate_percentage_can_pay_out_the_whole_pot() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Charity::donate(Origin::signed(1), 10, vec![], None));
+
+		assert_ok!(Charity::allocate_percentage(
+			RawOrigin::Root.into(),
+			3,
+			Perbill::one()
+		));
+		assert_eq!(Balances::free_balance(3), 1 + 11);
+		assert_eq!(Charity::pot(), 0);
+		assert_noop!(
+			Charity::allocate_percentage(RawOrigin::Root.into(), 3, Perbill::one()),
+			Error::<TestRuntime, DefaultInstance>::InsufficientPot
+		);
+	})
+}
+
+#[test]
+fn low_pot_warning_fires_once_per_downward_crossing() {
+	new_test_ext().execute_with(|| {
+		LowPotThreshold::set(5);
+		let warnings = || {
+			System::events()
+				.into_iter()
+				.filter(|r| matches!(r.event, Event::charity(RawEvent::PotRunningLow(_))))
+				.count()
+		};
+
+		assert_ok!(Charity::donate(Origin::signed(1), 10, vec![], None));
+		Charity::on_finalize(1);
+		assert_eq!(warnings(), 0);
+
+		assert_ok!(Charity::allocate(RawOrigin::Root.into(), 2, 8));
+		Charity::on_finalize(2);
+		Charity::on_finalize(3);
+		assert_eq!(warnings(), 1);
+		assert!(System::events()
+			.iter()
+			.any(|r| r.event == Event::charity(RawEvent::PotRunningLow(3))));
+
+		// Recovering above the threshold re-arms the warning
+		assert_ok!(Charity::donate(Origin::signed(2), 5, vec![], None));
+		Charity::on_finalize(4);
+		assert!(!Charity::was_low());
+		assert_eq!(warnings(), 1);
+
+		assert_ok!(Charity::allocate(RawOrigin::Root.into(), 2, 6));
+		Charity::on_finalize(6);
+		assert_eq!(warnings(), 2);
+	})
+}
+
+#[test]
+fn donate_and_allocate_passes_funds_straight_through() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Charity::donate_and_allocate(Origin::signed(1), 3, 10));
+
+		assert_eq!(Charity::pot(), Balances::minimum_balance());
+		assert_eq!(Balances::free_balance(1), 13 - 10);
+		assert_eq!(Balances::free_balance(3), 1 + 10);
+		assert_eq!(Charity::donation_of(1), 10);
+		assert_eq!(Charity::total_donated(), 10);
+		assert!(Charity::receipt(0).is_some());
+
+		let events = System::events()
+			.into_iter()
+			.filter_map(|r| match r.event {
+				Event::charity(e @ RawEvent::DonationReceived(..))
+				| Event::charity(e @ RawEvent::FundsAllocated(..)) => Some(e),
+				_ => None,
+			})
+			.collect::<Vec<_>>();
+		assert_eq!(
+			events,
+			vec![
+				RawEvent::DonationReceived(1, 10, 10, 11, Default::default(), 1, 10, 0),
+				RawEvent::FundsAllocated(3, 10, 1),
+			]
+		);
+	})
+}
+
+#[test]
+fn allocations_are_capped_per_block() {
+	new_test_ext().execute_with(|| {
+		MaxSpendPerBlock::set(10);
+		System::set_block_number(1);
+		assert_ok!(Charity::donate(Origin::signed(5), 15, vec![], None));
+
+		// Up to the limit is fine
+		assert_ok!(Charity::allocate(RawOrigin::Root.into(), 2, 6));
+		assert_ok!(Charity::allocate_batch(
+			RawOrigin::Root.into(),
+			vec![(3, 4), (4, 1)]
+		));
+		assert_eq!(Charity::spent_this_block(), 10);
+		assert!(System::events()
+			.iter()
+			.any(|r| r.event == Event::charity(RawEvent::AllocationFailed(4, 1))));
+
+		assert_noop!(
+			Charity::allocate(RawOrigin::Root.into(), 2, 1),
+			Error::<TestRuntime, DefaultInstance>::BlockSpendLimitReached
+		);
+
+		// The allowance is renewed in the next block
+		System::set_block_number(2);
+		Charity::on_initialize(2);
+		assert_ok!(Charity::allocate(RawOrigin::Root.into(), 2, 5));
+		assert_eq!(Charity::pot(), 1);
+	})
+}
+
+#[test]
+fn donors_passing_funds_through_dont_use_up_the_block_cap() {
+	new_test_ext().execute_with(|| {
+		MaxSpendPerBlock::set(10);
+		assert_ok!(Charity::donate(Origin::signed(5), 10, vec![], None));
+
+		assert_ok!(Charity::donate_and_allocate(Origin::signed(1), 1, 10));
+		assert_eq!(Charity::spent_this_block(), 0);
+		assert_ok!(Charity::allocate(RawOrigin::Root.into(), 2, 10));
+	})
+}
+
+#[test]
+fn queued_payments_too_large_for_any_block_are_rejected() {
+	new_test_ext().execute_with(|| {
+		MaxSpendPerBlock::set(5);
+		assert_ok!(Charity::donate(Origin::signed(5), 15, vec![], None));
+
+		assert_noop!(
+			Charity::propose_allocation(RawOrigin::Root.into(), 2, 6),
+			Error::<TestRuntime, DefaultInstance>::ExceedsBlockSpendLimit
+		);
+		assert_noop!(
+			Charity::allocate_with_vesting(RawOrigin::Root.into(), 2, 12, 6, 2),
+			Error::<TestRuntime, DefaultInstance>::ExceedsBlockSpendLimit
+		);
+		// A grant smaller than one instalment is paid in one go, so only its total matters
+		assert_ok!(Charity::allocate_with_vesting(
+			RawOrigin::Root.into(),
+			2,
+			5,
+			6,
+			2
+		));
+		assert_ok!(Charity::propose_allocation(RawOrigin::Root.into(), 3, 5));
+		assert_eq!(Charity::locked_funds(), 10);
+	})
+}
+
+#[test]
+fn every_allocation_route_counts_towards_the_block_cap() {
+	new_test_ext().execute_with(|| {
+		MaxSpendPerBlock::set(10);
+		System::set_block_number(1);
+		assert_ok!(Charity::donate_to_cause(
+			Origin::signed(5),
+			b"education".to_vec(),
+			15
+		));
+
+		assert_ok!(Charity::allocate_from_cause(
+			RawOrigin::Root.into(),
+			b"education".to_vec(),
+			2,
+			6
+		));
+		assert_eq!(Charity::spent_this_block(), 6);
+		assert_noop!(
+			Charity::allocate_all(RawOrigin::Root.into(), 2, true),
+			Error::<TestRuntime, DefaultInstance>::BlockSpendLimitReached
+		);
+		assert_noop!(
+			Charity::allocate_proportional(
+				RawOrigin::Root.into(),
+				vec![(3, Perbill::from_percent(50))],
+				RemainderPolicy::KeepInPot,
+			),
+			Error::<TestRuntime, DefaultInstance>::BlockSpendLimitReached
+		);
+
+		// A queued allocation waits for a block whose allowance can cover it
+		assert_ok!(Charity::propose_allocation(RawOrigin::Root.into(), 3, 4));
+		MaxSpendPerBlock::set(3);
+		let due = 1 + AllocationDelay::get();
+		run_to_block(due);
+		assert_eq!(Balances::free_balance(3), 1);
+
+		MaxSpendPerBlock::set(10);
+		run_to_block(due + 1);
+		assert_eq!(Balances::free_balance(3), 1 + 4);
+		assert_eq!(Charity::spent_this_block(), 4);
+	})
+}
+
+#[test]
+fn pot_share_of_is_the_donors_fraction_of_all_donations() {
+	use charity_runtime_api::runtime_decl_for_CharityApi::CharityApi;
+
+	new_test_ext().execute_with(|| {
+		assert_eq!(Charity::pot_share_of(&1), Perbill::zero());
+
+		assert_ok!(Charity::donate(Origin::signed(1), 6, vec![], None));
+		assert_ok!(Charity::donate(Origin::signed(2), 4, vec![], None));
+		assert_ok!(Charity::donate(Origin::signed(5), 2, vec![], None));
+
+		assert_eq!(Charity::pot_share_of(&1), Perbill::from_percent(50));
+		assert_eq!(
+			<TestRuntime as CharityApi<Block, u128, u64>>::pot_share_of(2),
+			Perbill::from_rational_approximation(1u32, 3u32)
+		);
+		assert_eq!(Charity::pot_share_of(&3), Perbill::zero());
+	})
+}
+
+#[test]
+fn pot_share_of_matches_the_refund_despite_an_endowment() {
+	use charity_runtime_api::runtime_decl_for_CharityApi::CharityApi;
+
+	ExtBuilder::default()
+		.with_endowment(20)
+		.build()
+		.execute_with(|| {
+			assert_ok!(Charity::donate(Origin::signed(1), 6, vec![], None));
+			assert_ok!(Charity::donate(Origin::signed(2), 4, vec![], None));
+			// The endowment is in `TotalDonated` but credited to no donor
+			assert_eq!(Charity::total_donated(), 30);
+			assert_eq!(Charity::pot_share_of(&1), Perbill::from_percent(60));
+			// Clients asking through the runtime API see the same share
+			assert_eq!(
+				<TestRuntime as CharityApi<Block, u128, u64>>::pot_share_of(1),
+				Perbill::from_percent(60)
+			);
+
+			let spendable = Charity::pot() - Balances::minimum_balance();
+			let share = Charity::pot_share_of(&1).mul_floor(spendable);
+			assert_ok!(Charity::refund_all(RawOrigin::Root.into(), 2));
+			assert_eq!(Balances::free_balance(1), 13 - 6 + share);
+		})
+}
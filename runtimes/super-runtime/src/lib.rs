@@ -19,11 +19,13 @@ use frame_system as system;
 use pallet_transaction_payment::CurrencyAdapter;
 use sp_api::impl_runtime_apis;
 use sp_core::{OpaqueMetadata, H256};
-use sp_runtime::traits::{BlakeTwo256, Block as BlockT, IdentifyAccount, IdentityLookup, Verify};
+use sp_runtime::traits::{
+	AccountIdConversion, BlakeTwo256, Block as BlockT, IdentifyAccount, IdentityLookup, Verify,
+};
 use sp_runtime::{
 	create_runtime_str, generic,
 	transaction_validity::{TransactionSource, TransactionValidity},
-	ApplyExtrinsicResult, MultiSignature,
+	ApplyExtrinsicResult, DispatchError, ModuleId, MultiSignature,
 };
 use sp_std::prelude::*;
 
@@ -33,10 +35,10 @@ use sp_version::RuntimeVersion;
 
 use frame_support::{
 	construct_runtime, parameter_types,
-	traits::Randomness,
+	traits::{ExistenceRequirement, Randomness},
 	weights::{
 		constants::{RocksDbWeight, WEIGHT_PER_SECOND},
-		IdentityFee,
+		IdentityFee, Weight,
 	},
 };
 #[cfg(any(feature = "std", test))]
@@ -215,15 +217,108 @@ impl pallet_sudo::Config for Runtime {
 	type Call = Call;
 }
 
+parameter_types! {
+	pub MaximumSchedulerWeight: Weight = Perbill::from_percent(80) * BlockWeights::get().max_block;
+	pub const MaxScheduledPerBlock: u32 = 50;
+}
+
+impl pallet_scheduler::Config for Runtime {
+	type Event = Event;
+	type Origin = Origin;
+	type PalletsOrigin = OriginCaller;
+	type Call = Call;
+	type MaximumWeight = MaximumSchedulerWeight;
+	type ScheduleOrigin = frame_system::EnsureRoot<AccountId>;
+	type MaxScheduledPerBlock = MaxScheduledPerBlock;
+	type WeightInfo = ();
+}
+
 // ---------------------- Recipe Pallet Configurations ----------------------
 
 impl basic_token::Config for Runtime {
 	type Event = Event;
 }
 
+parameter_types! {
+	pub const CharityModuleId: ModuleId = ModuleId(*b"Charity!");
+	pub const MinimumDonation: u128 = 10;
+	pub const DonationUnit: u128 = 1;
+	pub const MaxMemoLength: u32 = 64;
+	pub const UseReserved: bool = false;
+	pub const AllocationExistenceRequirement: ExistenceRequirement = ExistenceRequirement::AllowDeath;
+	pub const MaxPotBalance: u128 = u128::MAX;
+	pub const CharityOverflowId: ModuleId = ModuleId(*b"Charity+");
+	pub OverflowDestination: AccountId = CharityOverflowId::get().into_account();
+	pub const MaxCauseNameLength: u32 = 32;
+	pub const MaxCauses: u32 = 64;
+	pub const MaxBatchSize: u32 = 32;
+	pub const AllocationDelay: BlockNumber = 100;
+	pub Milestones: Vec<Balance> = Vec::new();
+	pub const AbsorbFraction: Perbill = Perbill::one();
+	pub const SnapshotInterval: BlockNumber = 600;
+	pub const MaxSnapshots: u32 = 336;
+	pub const MaxLeaderboard: u32 = 10;
+	pub const StreakWindow: BlockNumber = 14_400;
+	pub StreakThresholds: Vec<u32> = sp_std::vec![7, 30, 100];
+	pub const DonationFee: Perbill = Perbill::zero();
+	pub const CharityFeeId: ModuleId = ModuleId(*b"Charity%");
+	pub FeeCollector: AccountId = CharityFeeId::get().into_account();
+	pub const MinPotReserve: Balance = 0;
+	pub const DonationCooldown: BlockNumber = 0;
+	pub const DonationPeriod: BlockNumber = 0;
+	pub const PerDonorPeriodCap: Balance = Balance::max_value();
+	pub const LowPotThreshold: Balance = 0;
+	pub const MaxSpendPerBlock: Balance = Balance::max_value();
+	pub const AllocationFee: Perbill = Perbill::zero();
+	pub const CharityOperationsId: ModuleId = ModuleId(*b"Charity&");
+	pub OperationsAccount: AccountId = CharityOperationsId::get().into_account();
+	pub const MaxRecurringDonations: u32 = 1_000;
+	pub const RecurringDonationDeposit: Balance = 100;
+}
+
 impl charity::Config for Runtime {
 	type Event = Event;
 	type Currency = Balances;
+	type ModuleId = CharityModuleId;
+	type UseReserved = UseReserved;
+	type AllocationExistenceRequirement = AllocationExistenceRequirement;
+	type MinimumDonation = MinimumDonation;
+	type DonationUnit = DonationUnit;
+	type MaxMemoLength = MaxMemoLength;
+	type MaxPotBalance = MaxPotBalance;
+	type OverflowDestination = OverflowDestination;
+	type MaxCauseNameLength = MaxCauseNameLength;
+	type MaxCauses = MaxCauses;
+	type MaxBatchSize = MaxBatchSize;
+	type OnDonation = ();
+	type OnAllocation = ();
+	type DonationValidator = ();
+	type Assets = ();
+	type AllocationDelay = AllocationDelay;
+	type Milestones = Milestones;
+	type AbsorbFraction = AbsorbFraction;
+	type SnapshotInterval = SnapshotInterval;
+	type MaxSnapshots = MaxSnapshots;
+	type MaxLeaderboard = MaxLeaderboard;
+	type StreakWindow = StreakWindow;
+	type StreakThresholds = StreakThresholds;
+	type DonationFee = DonationFee;
+	type FeeCollector = FeeCollector;
+	type MinPotReserve = MinPotReserve;
+	type DonationCooldown = DonationCooldown;
+	type DonationPeriod = DonationPeriod;
+	type PerDonorPeriodCap = PerDonorPeriodCap;
+	type LowPotThreshold = LowPotThreshold;
+	type MaxSpendPerBlock = MaxSpendPerBlock;
+	type AllocationFee = AllocationFee;
+	type OperationsAccount = OperationsAccount;
+	type MaxRecurringDonations = MaxRecurringDonations;
+	type RecurringDonationDeposit = RecurringDonationDeposit;
+	type ScheduledCall = Call;
+	type PalletsOrigin = OriginCaller;
+	type Scheduler = Scheduler;
+	type AllocationOrigin = frame_system::EnsureRoot<AccountId>;
+	type WeightInfo = ();
 }
 
 impl compounding_interest::Config for Runtime {
@@ -352,10 +447,11 @@ construct_runtime!(
 		Balances: pallet_balances::{Module, Call, Storage, Config<T>, Event<T>},
 		RandomnessCollectiveFlip: pallet_randomness_collective_flip::{Module, Call, Storage},
 		Sudo: pallet_sudo::{Module, Call, Config<T>, Storage, Event<T>},
+		Scheduler: pallet_scheduler::{Module, Call, Storage, Event<T>},
 		TransactionPayment: pallet_transaction_payment::{Module, Storage},
 		// The Recipe Pallets
 		BasicToken: basic_token::{Module, Call, Storage, Event<T>},
-		Charity: charity::{Module, Call, Storage, Config, Event<T>},
+		Charity: charity::{Module, Call, Storage, Config<T>, Event<T>},
 		CheckMembershipLoose: check_membership_loose::{Module, Call, Event<T>},
 		CheckMembershipTight: check_membership_tight::{Module, Call, Event<T>},
 		CompoundingInterest: compounding_interest::{Module, Call, Storage, Event},
@@ -481,4 +577,42 @@ impl_runtime_apis! {
 			None
 		}
 	}
+
+	impl charity_runtime_api::CharityApi<Block, AccountId, Balance> for Runtime {
+		fn pot_balance() -> Balance {
+			Charity::pot()
+		}
+
+		fn pot_account() -> AccountId {
+			Charity::account_id()
+		}
+
+		fn can_allocate(amount: Balance) -> bool {
+			Charity::can_allocate(amount)
+		}
+
+		fn donation_rank(who: AccountId) -> Option<u32> {
+			Charity::donation_rank(&who)
+		}
+
+		fn simulate_allocation(amount: Balance) -> Result<Balance, DispatchError> {
+			Charity::simulate_allocation(amount)
+		}
+
+		fn causes() -> Vec<(Vec<u8>, Balance)> {
+			Charity::causes()
+		}
+
+		fn pot_share_of(who: AccountId) -> Perbill {
+			Charity::pot_share_of(&who)
+		}
+
+		fn pending_vesting(dest: AccountId) -> Balance {
+			Charity::pending_vesting(&dest)
+		}
+
+		fn stats() -> charity_runtime_api::CharityStats<Balance> {
+			Charity::stats()
+		}
+	}
 }